@@ -1,7 +1,9 @@
 use std::borrow::Cow;
 use std::fmt;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum AzuroErrorCode {
     SimulationRequired,
     DeltaOddThreshold,
@@ -13,6 +15,7 @@ pub enum AzuroErrorCode {
     InvalidResponse,
     Configuration,
     Heartbeat,
+    Cassette,
     Unknown,
 }
 
@@ -29,6 +32,7 @@ impl AzuroErrorCode {
             AzuroErrorCode::InvalidResponse => "E-AZU-INVALID-RESPONSE",
             AzuroErrorCode::Configuration => "E-AZU-CONFIG",
             AzuroErrorCode::Heartbeat => "E-AZU-HEARTBEAT",
+            AzuroErrorCode::Cassette => "E-AZU-CASSETTE",
             AzuroErrorCode::Unknown => "E-AZU-UNKNOWN",
         }
     }
@@ -40,7 +44,7 @@ impl fmt::Display for AzuroErrorCode {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AzuroError {
     code: AzuroErrorCode,
     message: Cow<'static, str>,
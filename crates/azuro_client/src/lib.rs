@@ -1,6 +1,7 @@
 #![forbid(unsafe_code)]
 
 pub mod error;
+pub mod replay;
 
 pub use error::{AzuroError, AzuroErrorCode};
 use parking_lot::RwLock;
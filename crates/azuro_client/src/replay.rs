@@ -0,0 +1,295 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::warn;
+
+use crate::{AzuroError, AzuroErrorCode, QuoteEngine, QuoteEngineResponse, QuoteRequest};
+
+/// One recorded `QuoteEngine` call, stored as a single JSON line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CassetteEntry {
+    pub seq: u64,
+    pub call: String,
+    pub request: Value,
+    pub response: Value,
+    pub latency_us: u64,
+}
+
+const CALL_FETCH_QUOTE: &str = "engine.fetch_quote";
+const CALL_MAX_PAYOUT: &str = "engine.max_payout";
+
+/// Append-only JSON-lines sink for recorded engine calls.
+pub struct CassetteWriter {
+    sink: Mutex<(u64, Box<dyn Write + Send>)>,
+}
+
+impl CassetteWriter {
+    pub fn new(sink: impl Write + Send + 'static) -> Self {
+        Self { sink: Mutex::new((0, Box::new(sink))) }
+    }
+
+    pub fn create(path: impl AsRef<Path>) -> Result<Self, AzuroError> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|err| {
+            cassette_error("failed to create cassette").with_detail(format!("{}: {err}", path.display()))
+        })?;
+        Ok(Self::new(file))
+    }
+
+    fn record<Req: Serialize, Res: Serialize>(
+        &self,
+        call: &str,
+        request: &Req,
+        response: &Result<Res, AzuroError>,
+        latency: Duration,
+    ) -> Result<(), AzuroError> {
+        let mut guard = self.sink.lock();
+        let entry = CassetteEntry {
+            seq: guard.0,
+            call: call.to_string(),
+            request: to_value(request)?,
+            response: to_value(response)?,
+            latency_us: u64::try_from(latency.as_micros()).unwrap_or(u64::MAX),
+        };
+        let line = serde_json::to_string(&entry)
+            .map_err(|err| cassette_error("failed to encode entry").with_detail(err.to_string()))?;
+        writeln!(guard.1, "{line}")
+            .and_then(|_| guard.1.flush())
+            .map_err(|err| cassette_error("failed to write entry").with_detail(err.to_string()))?;
+        guard.0 += 1;
+        Ok(())
+    }
+
+    /// Recording must never mask the live result, so write errors are only logged.
+    fn record_or_warn<Req: Serialize, Res: Serialize>(
+        &self,
+        call: &str,
+        request: &Req,
+        response: &Result<Res, AzuroError>,
+        latency: Duration,
+    ) {
+        if let Err(err) = self.record(call, request, response, latency) {
+            warn!(call, error = %err, "failed to record cassette entry");
+        }
+    }
+}
+
+/// Wraps a live `QuoteEngine` and records each call to a cassette.
+pub struct RecordingEngine<E: QuoteEngine> {
+    inner: E,
+    cassette: Arc<CassetteWriter>,
+}
+
+impl<E: QuoteEngine> RecordingEngine<E> {
+    pub fn new(inner: E, cassette: Arc<CassetteWriter>) -> Self { Self { inner, cassette } }
+
+    pub fn into_inner(self) -> E { self.inner }
+}
+
+impl<E: QuoteEngine> QuoteEngine for RecordingEngine<E> {
+    fn fetch_quote(&self, request: &QuoteRequest) -> Result<QuoteEngineResponse, AzuroError> {
+        let started = Instant::now();
+        let response = self.inner.fetch_quote(request);
+        self.cassette.record_or_warn(CALL_FETCH_QUOTE, request, &response, started.elapsed());
+        response
+    }
+
+    fn max_payout(&self) -> Result<f64, AzuroError> {
+        let started = Instant::now();
+        let response = self.inner.max_payout();
+        self.cassette.record_or_warn(CALL_MAX_PAYOUT, &(), &response, started.elapsed());
+        response
+    }
+}
+
+/// Serves a cassette back in recorded order, optionally sleeping for the recorded latency.
+pub struct ReplayEngine {
+    calls: Mutex<HashMap<String, VecDeque<CassetteEntry>>>,
+    with_latency: bool,
+    strict: bool,
+}
+
+impl ReplayEngine {
+    pub fn from_entries(entries: impl IntoIterator<Item = CassetteEntry>) -> Self {
+        let mut entries: Vec<_> = entries.into_iter().collect();
+        entries.sort_by_key(|entry| entry.seq);
+        let mut calls: HashMap<String, VecDeque<CassetteEntry>> = HashMap::new();
+        for entry in entries {
+            calls.entry(entry.call.clone()).or_default().push_back(entry);
+        }
+        Self { calls: Mutex::new(calls), with_latency: false, strict: true }
+    }
+
+    pub fn from_reader(reader: impl BufRead) -> Result<Self, AzuroError> {
+        let mut entries = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.map_err(|err| cassette_error("failed to read cassette").with_detail(err.to_string()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = serde_json::from_str(&line).map_err(|err| {
+                cassette_error("malformed cassette entry").with_detail(format!("line {}: {err}", index + 1))
+            })?;
+            entries.push(entry);
+        }
+        Ok(Self::from_entries(entries))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AzuroError> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|err| {
+            cassette_error("failed to open cassette").with_detail(format!("{}: {err}", path.display()))
+        })?;
+        Self::from_reader(BufReader::new(file))
+    }
+
+    pub fn with_latency(mut self, enabled: bool) -> Self {
+        self.with_latency = enabled;
+        self
+    }
+
+    /// When strict (the default), a request differing from the recorded one is an error.
+    pub fn strict(mut self, enabled: bool) -> Self {
+        self.strict = enabled;
+        self
+    }
+
+    pub fn remaining(&self) -> usize { self.calls.lock().values().map(VecDeque::len).sum() }
+
+    fn serve<Req: Serialize, Res: DeserializeOwned>(&self, call: &str, request: &Req) -> Result<Res, AzuroError> {
+        let entry = self
+            .calls
+            .lock()
+            .get_mut(call)
+            .and_then(VecDeque::pop_front)
+            .ok_or_else(|| cassette_error("cassette exhausted").with_detail(format!("call={call}")))?;
+        if self.strict {
+            let actual = to_value(request)?;
+            if actual != entry.request {
+                return Err(cassette_error("recorded request mismatch").with_detail(format!(
+                    "call={call}, seq={}, recorded={}, got={actual}",
+                    entry.seq, entry.request
+                )));
+            }
+        }
+        if self.with_latency {
+            std::thread::sleep(Duration::from_micros(entry.latency_us));
+        }
+        serde_json::from_value::<Result<Res, AzuroError>>(entry.response)
+            .map_err(|err| cassette_error("malformed recorded response").with_detail(err.to_string()))?
+    }
+}
+
+impl QuoteEngine for ReplayEngine {
+    fn fetch_quote(&self, request: &QuoteRequest) -> Result<QuoteEngineResponse, AzuroError> {
+        self.serve(CALL_FETCH_QUOTE, request)
+    }
+
+    fn max_payout(&self) -> Result<f64, AzuroError> { self.serve(CALL_MAX_PAYOUT, &()) }
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<Value, AzuroError> {
+    serde_json::to_value(value).map_err(|err| cassette_error("failed to encode value").with_detail(err.to_string()))
+}
+
+fn cassette_error(message: &'static str) -> AzuroError {
+    AzuroError::new(AzuroErrorCode::Cassette, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AzuroClient, AzuroConfig};
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+    }
+
+    struct BrokenSink;
+
+    impl Write for BrokenSink {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> { Err(std::io::Error::other("disk full")) }
+
+        fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+    }
+
+    struct LiveEngine;
+
+    impl QuoteEngine for LiveEngine {
+        fn fetch_quote(&self, request: &QuoteRequest) -> Result<QuoteEngineResponse, AzuroError> {
+            std::thread::sleep(Duration::from_millis(3));
+            if request.stake > 500.0 {
+                return Err(AzuroError::new(AzuroErrorCode::Network, "upstream reset").with_detail("status=502"));
+            }
+            Ok(QuoteEngineResponse { quoted_odd: 1.84, marginal_odd: 1.85, max_payout_limit: 1000.0, amount_token: Some(49.0) })
+        }
+
+        fn max_payout(&self) -> Result<f64, AzuroError> { Ok(1000.0) }
+    }
+
+    #[test]
+    fn cassette_write_errors_do_not_mask_live_responses() {
+        let engine = RecordingEngine::new(LiveEngine, Arc::new(CassetteWriter::new(BrokenSink)));
+        let response = engine.fetch_quote(&QuoteRequest { stake: 50.0, amount_token: None }).expect("live quote is reported");
+        assert_eq!(response.quoted_odd, 1.84);
+    }
+
+    fn record_session() -> Vec<u8> {
+        let buffer = SharedBuffer::default();
+        let engine = RecordingEngine::new(LiveEngine, Arc::new(CassetteWriter::new(buffer.clone())));
+        let client = AzuroClient::new(AzuroConfig::default(), engine);
+        client.simulate_quote(&QuoteRequest { stake: 50.0, amount_token: None }).expect("quote");
+        client.simulate_quote(&QuoteRequest { stake: 600.0, amount_token: None }).expect_err("network error");
+        let bytes = buffer.0.lock().clone();
+        bytes
+    }
+
+    #[test]
+    fn recorded_session_replays_deterministically() {
+        let bytes = record_session();
+        let replay = ReplayEngine::from_reader(bytes.as_slice()).expect("cassette");
+        assert_eq!(replay.remaining(), 3);
+        let client = AzuroClient::new(AzuroConfig::default(), replay);
+        let simulation = client.simulate_quote(&QuoteRequest { stake: 50.0, amount_token: None }).expect("replayed quote");
+        assert_eq!(simulation.amount_token, Some(49.0));
+        assert!((simulation.expected_payout - 92.5).abs() < f64::EPSILON);
+        let err = client.simulate_quote(&QuoteRequest { stake: 600.0, amount_token: None }).expect_err("replayed error");
+        assert_eq!(err.code(), AzuroErrorCode::Network);
+        assert_eq!(err.detail(), Some("status=502"));
+    }
+
+    #[test]
+    fn strict_replay_rejects_diverging_request() {
+        let replay = ReplayEngine::from_reader(record_session().as_slice()).expect("cassette");
+        let err = replay.fetch_quote(&QuoteRequest { stake: 75.0, amount_token: None }).expect_err("mismatch");
+        assert_eq!(err.code_str(), "E-AZU-CASSETTE");
+    }
+
+    #[test]
+    fn replay_can_reproduce_recorded_latency() {
+        let replay = ReplayEngine::from_reader(record_session().as_slice())
+            .expect("cassette")
+            .with_latency(true)
+            .strict(false);
+        let started = Instant::now();
+        replay.fetch_quote(&QuoteRequest { stake: 1.0, amount_token: None }).expect("quote");
+        assert!(started.elapsed() >= Duration::from_millis(3));
+    }
+}
@@ -0,0 +1,31 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use execution::batch::{batch_after_commission, batch_after_commission_f64};
+use execution::odds_converter::decimals_after_commission;
+use rust_decimal::Decimal;
+
+fn market_list(count: usize) -> Vec<f64> {
+    (0..count).map(|index| 1.2 + (index % 400) as f64 * 0.025).collect()
+}
+
+fn after_commission(c: &mut Criterion) {
+    let mut group = c.benchmark_group("after_commission");
+    let rate = Decimal::new(2, 2);
+    for count in [1_000, 100_000] {
+        let fast = market_list(count);
+        let exact: Vec<Decimal> = fast.iter().map(|odds| Decimal::try_from(*odds).expect("finite odds")).collect();
+
+        group.bench_with_input(BenchmarkId::new("elementwise", count), &exact, |b, exact| {
+            b.iter(|| decimals_after_commission(black_box(exact), rate))
+        });
+        group.bench_with_input(BenchmarkId::new("batch_decimal", count), &exact, |b, exact| {
+            b.iter(|| batch_after_commission(black_box(exact), rate))
+        });
+        group.bench_with_input(BenchmarkId::new("batch_f64", count), &fast, |b, fast| {
+            b.iter(|| batch_after_commission_f64(black_box(fast), 0.02))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, after_commission);
+criterion_main!(benches);
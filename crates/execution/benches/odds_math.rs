@@ -0,0 +1,64 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use execution::net_margin::{compute_net_margin, meets_net_margin_threshold};
+use execution::odds_converter::normalized_probabilities;
+use execution::screening::{fast_net_margin, fast_normalized_probabilities, screen_and_verify, FastFeeModel, FastNetMarginInputs};
+use rust_decimal::prelude::*;
+
+/// Deterministic spread of pairs, about a fifth of them above a 1.5 % threshold.
+fn candidates(count: usize) -> Vec<FastNetMarginInputs> {
+    (0..count)
+        .map(|index| {
+            let step = (index % 97) as f64 / 97.0;
+            FastNetMarginInputs {
+                odds_sx: 1.80 + 0.45 * step,
+                odds_azuro: 2.26 - 0.40 * step,
+                fee_sx: FastFeeModel::OnWinnings(0.004),
+                fee_azuro: FastFeeModel::Embedded,
+                payout_usd: 100.0,
+                gas_usd: 0.07,
+                slippage_sx: 0.001,
+                slippage_azuro: 0.001,
+            }
+        })
+        .collect()
+}
+
+fn net_margin(c: &mut Criterion) {
+    let mut group = c.benchmark_group("net_margin");
+    for count in [1_000, 10_000] {
+        let fast = candidates(count);
+        let exact: Vec<_> = fast.iter().map(|candidate| candidate.to_decimal().expect("finite inputs")).collect();
+        let threshold = Decimal::new(15, 3);
+
+        group.bench_with_input(BenchmarkId::new("f64", count), &fast, |b, fast| {
+            b.iter(|| fast.iter().filter_map(|candidate| fast_net_margin(black_box(*candidate))).filter(|net| *net >= 0.015).count())
+        });
+        group.bench_with_input(BenchmarkId::new("decimal", count), &exact, |b, exact| {
+            b.iter(|| {
+                exact
+                    .iter()
+                    .filter(|inputs| meets_net_margin_threshold(black_box(**inputs), threshold).is_ok_and(|(_, meets)| meets))
+                    .count()
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("screen_and_verify", count), &fast, |b, fast| {
+            b.iter(|| screen_and_verify(black_box(fast), threshold).expect("valid threshold").len())
+        });
+    }
+    group.finish();
+
+    let single = candidates(1)[0];
+    let exact = single.to_decimal().expect("finite inputs");
+    c.bench_function("net_margin/single_f64", |b| b.iter(|| fast_net_margin(black_box(single))));
+    c.bench_function("net_margin/single_decimal", |b| b.iter(|| compute_net_margin(black_box(exact))));
+}
+
+fn probabilities(c: &mut Criterion) {
+    let fast = [1.85, 3.40, 4.20];
+    let exact: Vec<Decimal> = fast.iter().map(|odds| Decimal::from_f64(*odds).expect("finite odds")).collect();
+    c.bench_function("normalized_probabilities/f64", |b| b.iter(|| fast_normalized_probabilities(black_box(&fast))));
+    c.bench_function("normalized_probabilities/decimal", |b| b.iter(|| normalized_probabilities(black_box(&exact))));
+}
+
+criterion_group!(benches, net_margin, probabilities);
+criterion_main!(benches);
@@ -0,0 +1,167 @@
+use rust_decimal::Decimal;
+
+use crate::odds_converter::{decimal_after_commission, decimal_before_commission, validate_commission, ConversionError};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Struct-of-arrays batch output: `values[i]` is meaningful only when bit `i` of the error mask is clear
+/// (failed slots hold the default value).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BatchResult<T> {
+    values: Vec<T>,
+    /// Bit `i % 64` of word `i / 64` is set when element `i` failed.
+    errors: Vec<u64>,
+}
+
+impl<T> BatchResult<T> {
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn values(&self) -> &[T] {
+        &self.values
+    }
+
+    pub fn error_mask(&self) -> &[u64] {
+        &self.errors
+    }
+
+    pub fn is_ok(&self, index: usize) -> bool {
+        index < self.values.len() && self.errors[index / WORD_BITS] & (1 << (index % WORD_BITS)) == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.is_ok(index).then(|| &self.values[index])
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.errors.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn failed_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.values.len()).filter(|&index| !self.is_ok(index))
+    }
+}
+
+/// Applies `convert` to every element; one word of the mask per 64-element chunk, so chunks can run in parallel.
+fn map_batch<I, T, E>(inputs: &[I], convert: impl Fn(I) -> Result<T, E> + Sync) -> BatchResult<T>
+where
+    I: Copy + Sync,
+    T: Default + Send,
+{
+    let convert_chunk = |chunk: &[I]| {
+        let mut values = Vec::with_capacity(chunk.len());
+        let mut mask = 0u64;
+        for (offset, &input) in chunk.iter().enumerate() {
+            match convert(input) {
+                Ok(value) => values.push(value),
+                Err(_) => {
+                    values.push(T::default());
+                    mask |= 1 << offset;
+                }
+            }
+        }
+        (values, mask)
+    };
+
+    #[cfg(feature = "rayon")]
+    let chunks: Vec<(Vec<T>, u64)> = {
+        use rayon::prelude::*;
+        inputs.par_chunks(WORD_BITS).map(convert_chunk).collect()
+    };
+    #[cfg(not(feature = "rayon"))]
+    let chunks: Vec<(Vec<T>, u64)> = inputs.chunks(WORD_BITS).map(convert_chunk).collect();
+
+    let mut values = Vec::with_capacity(inputs.len());
+    let mut errors = Vec::with_capacity(chunks.len());
+    for (chunk, mask) in chunks {
+        values.extend(chunk);
+        errors.push(mask);
+    }
+    BatchResult { values, errors }
+}
+
+fn validate_commission_f64(rate: f64) -> Result<(), ConversionError> {
+    if !(0.0..1.0).contains(&rate) {
+        return Err(ConversionError::InvalidCommission);
+    }
+    Ok(())
+}
+
+fn valid_odds_f64(odds: f64) -> Result<f64, ConversionError> {
+    if odds > 1.0 && odds.is_finite() {
+        Ok(odds)
+    } else {
+        Err(ConversionError::InvalidDecimal)
+    }
+}
+
+/// `decimals_after_commission` that flags bad odds instead of failing; only an invalid rate fails the batch.
+pub fn batch_after_commission(decimals: &[Decimal], commission_rate: Decimal) -> Result<BatchResult<Decimal>, ConversionError> {
+    validate_commission(commission_rate)?;
+    Ok(map_batch(decimals, |decimal| decimal_after_commission(decimal, commission_rate)))
+}
+
+pub fn batch_before_commission(decimals: &[Decimal], commission_rate: Decimal) -> Result<BatchResult<Decimal>, ConversionError> {
+    validate_commission(commission_rate)?;
+    Ok(map_batch(decimals, |decimal| decimal_before_commission(decimal, commission_rate)))
+}
+
+pub fn batch_after_commission_f64(decimals: &[f64], commission_rate: f64) -> Result<BatchResult<f64>, ConversionError> {
+    validate_commission_f64(commission_rate)?;
+    let multiplier = 1.0 - commission_rate;
+    Ok(map_batch(decimals, |odds| valid_odds_f64(odds).map(|odds| 1.0 + (odds - 1.0) * multiplier)))
+}
+
+pub fn batch_before_commission_f64(decimals: &[f64], commission_rate: f64) -> Result<BatchResult<f64>, ConversionError> {
+    validate_commission_f64(commission_rate)?;
+    let multiplier = 1.0 - commission_rate;
+    Ok(map_batch(decimals, |odds| valid_odds_f64(odds).map(|odds| 1.0 + (odds - 1.0) / multiplier)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::odds_converter::decimals_after_commission;
+    use std::str::FromStr;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).expect("valid decimal")
+    }
+
+    #[test]
+    fn bad_odds_are_flagged_without_failing_the_batch() {
+        let mut decimals = vec![dec("2.5"); 130];
+        decimals[3] = dec("0.9");
+        decimals[129] = Decimal::ONE;
+        let batch = batch_after_commission(&decimals, dec("0.05")).expect("valid rate");
+
+        assert_eq!(batch.len(), 130);
+        assert_eq!(batch.error_mask(), &[1 << 3, 0, 1 << 1]);
+        assert_eq!(batch.error_count(), 2);
+        assert_eq!(batch.failed_indices().collect::<Vec<_>>(), vec![3, 129]);
+        assert_eq!(batch.get(3), None);
+        assert_eq!(batch.get(0), Some(&dec("2.425")));
+        assert!(decimals_after_commission(&decimals, dec("0.05")).is_err());
+
+        assert_eq!(batch_before_commission(&decimals, dec("1.0")).unwrap_err(), ConversionError::InvalidCommission);
+    }
+
+    #[test]
+    fn f64_batches_match_decimal_batches() {
+        let odds = [1.85, 2.05, f64::NAN, 0.5, 12.0];
+        let fast = batch_before_commission_f64(&odds, 0.02).expect("valid rate");
+        let exact = batch_before_commission(&[dec("1.85"), dec("2.05"), Decimal::ZERO, dec("0.5"), dec("12")], dec("0.02")).expect("valid rate");
+
+        assert_eq!(fast.error_mask(), exact.error_mask());
+        for index in [0, 1, 4] {
+            let exact = exact.get(index).unwrap().to_string().parse::<f64>().unwrap();
+            assert!((fast.get(index).unwrap() - exact).abs() < 1e-12);
+        }
+        assert!(batch_after_commission_f64(&odds, -0.1).is_err());
+    }
+}
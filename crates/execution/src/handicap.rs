@@ -0,0 +1,157 @@
+use std::cmp::Ordering;
+
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+const QUARTER: Decimal = Decimal::from_parts(25, 0, 0, false, 2);
+const HALF: Decimal = Decimal::from_parts(5, 0, 0, false, 1);
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum HandicapError {
+    #[error("handicap line {0} is not a multiple of 0.25")]
+    InvalidLine(Decimal),
+}
+
+/// Asian handicap line, a multiple of 0.25. Quarter lines (-0.25, -0.75, +1.25…) split the stake
+/// evenly across the two neighbouring half-lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HandicapLine(Decimal);
+
+impl HandicapLine {
+    pub fn new(line: Decimal) -> Result<Self, HandicapError> {
+        if !(line / QUARTER).fract().is_zero() {
+            return Err(HandicapError::InvalidLine(line));
+        }
+        Ok(Self(line.normalize()))
+    }
+
+    pub fn value(self) -> Decimal {
+        self.0
+    }
+
+    pub fn is_quarter(self) -> bool {
+        !(self.0 / HALF).fract().is_zero()
+    }
+
+    /// Constituent bets as `(line, stake share)`: `-0.75` → `[(-0.5, 0.5), (-1, 0.5)]`, `-1` → `[(-1, 1)]`.
+    pub fn components(self) -> Vec<(Decimal, Decimal)> {
+        if self.is_quarter() {
+            vec![((self.0 + QUARTER).normalize(), HALF), ((self.0 - QUARTER).normalize(), HALF)]
+        } else {
+            vec![(self.0, Decimal::ONE)]
+        }
+    }
+
+    /// Settles a bet on a side that finished `goal_difference` goals ahead (negative when behind).
+    pub fn settle(self, goal_difference: i64) -> Settlement {
+        let result: Decimal = self
+            .components()
+            .into_iter()
+            .map(|(line, share)| match (Decimal::from(goal_difference) + line).cmp(&Decimal::ZERO) {
+                Ordering::Greater => share,
+                Ordering::Equal => Decimal::ZERO,
+                Ordering::Less => -share,
+            })
+            .sum();
+        match result.normalize() {
+            r if r == Decimal::ONE => Settlement::Win,
+            r if r == HALF => Settlement::HalfWin,
+            r if r.is_zero() => Settlement::Push,
+            r if r == -HALF => Settlement::HalfLoss,
+            _ => Settlement::Loss,
+        }
+    }
+}
+
+/// Outcome of one handicap bet once the final score is known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Settlement {
+    Win,
+    HalfWin,
+    Push,
+    HalfLoss,
+    Loss,
+}
+
+impl Settlement {
+    /// Amount returned per unit staked at `decimal_odds`, stake included.
+    pub fn payout(self, decimal_odds: Decimal) -> Decimal {
+        match self {
+            Settlement::Win => decimal_odds,
+            Settlement::HalfWin => (decimal_odds + Decimal::ONE) / Decimal::TWO,
+            Settlement::Push => Decimal::ONE,
+            Settlement::HalfLoss => HALF,
+            Settlement::Loss => Decimal::ZERO,
+        }
+    }
+}
+
+/// Team a handicap bet backs; the goal difference is always taken home minus away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HandicapSide {
+    Home,
+    Away,
+}
+
+/// One venue's handicap bet: side, line from that side's perspective and decimal odds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandicapBet {
+    pub side: HandicapSide,
+    pub line: HandicapLine,
+    pub odds: Decimal,
+}
+
+impl HandicapBet {
+    pub fn settle(&self, home_goals: u32, away_goals: u32) -> Settlement {
+        self.settle_difference(i64::from(home_goals) - i64::from(away_goals))
+    }
+
+    pub(crate) fn settle_difference(&self, home_minus_away: i64) -> Settlement {
+        match self.side {
+            HandicapSide::Home => self.line.settle(home_minus_away),
+            HandicapSide::Away => self.line.settle(-home_minus_away),
+        }
+    }
+
+    pub fn payout(&self, home_goals: u32, away_goals: u32) -> Decimal {
+        self.settle(home_goals, away_goals).payout(self.odds)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn line(value: &str) -> HandicapLine {
+        HandicapLine::new(Decimal::from_str(value).expect("valid decimal")).expect("valid line")
+    }
+
+    #[test]
+    fn quarter_lines_split_into_half_lines() {
+        assert_eq!(line("-0.25").components(), vec![(Decimal::ZERO, HALF), (Decimal::from_str("-0.5").unwrap(), HALF)]);
+        assert_eq!(line("1.75").components().len(), 2);
+        assert_eq!(line("-1").components(), vec![(Decimal::NEGATIVE_ONE, Decimal::ONE)]);
+        assert!(!line("0.5").is_quarter());
+        assert_eq!(HandicapLine::new(Decimal::from_str("0.3").unwrap()), Err(HandicapError::InvalidLine(Decimal::from_str("0.3").unwrap())));
+    }
+
+    #[test]
+    fn settles_every_outcome_class() {
+        assert_eq!(line("-0.25").settle(0), Settlement::HalfLoss);
+        assert_eq!(line("-0.25").settle(1), Settlement::Win);
+        assert_eq!(line("0.25").settle(0), Settlement::HalfWin);
+        assert_eq!(line("-0.75").settle(1), Settlement::HalfWin);
+        assert_eq!(line("-1").settle(1), Settlement::Push);
+        assert_eq!(line("-1.25").settle(1), Settlement::HalfLoss);
+        assert_eq!(line("0.5").settle(-1), Settlement::Loss);
+    }
+
+    #[test]
+    fn away_bets_read_the_score_from_their_side() {
+        let bet = HandicapBet { side: HandicapSide::Away, line: line("0.25"), odds: Decimal::from_str("1.9").unwrap() };
+        assert_eq!(bet.settle(1, 1), Settlement::HalfWin);
+        assert_eq!(bet.payout(1, 1), Decimal::from_str("1.45").unwrap());
+        assert_eq!(bet.payout(2, 1), Decimal::ZERO);
+    }
+}
@@ -1,4 +1,8 @@
 #![forbid(unsafe_code)]
 
+pub mod batch;
+pub mod handicap;
 pub mod net_margin;
 pub mod odds_converter;
+pub mod screening;
+pub mod settlement;
@@ -1,22 +1,75 @@
 use rust_decimal::Decimal;
 use thiserror::Error;
 
+use crate::handicap::{HandicapBet, HandicapError, HandicapSide};
+
+/// Margins are fractions of the guaranteed payout `payout_usd`; cost totals are USD for both venues.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct NetMarginBreakdown {
     pub gross_margin: Decimal,
+    /// Venue fees in the scenario where they are highest.
     pub fees_total: Decimal,
     pub slippage_total: Decimal,
     pub gas_total: Decimal,
     pub net_margin: Decimal,
+    pub payout_usd: Decimal,
+}
+
+/// How a venue charges for a bet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeModel {
+    /// Commission on net winnings, charged only when the leg wins (SX maker/taker); see `decimal_after_commission`.
+    OnWinnings(Decimal),
+    /// Fraction of the stake, charged whatever the outcome.
+    OnStake(Decimal),
+    /// Margin already priced into the quoted odds (Azuro); nothing further is deducted.
+    Embedded,
+    /// Flat USD amount per bet.
+    FixedUsd(Decimal),
+}
+
+impl FeeModel {
+    fn validate(self, label: &'static str) -> Result<(), NetMarginError> {
+        match self {
+            FeeModel::OnWinnings(rate) | FeeModel::OnStake(rate) if rate < Decimal::ZERO || rate >= Decimal::ONE => {
+                Err(NetMarginError::InvalidFee(label))
+            }
+            FeeModel::FixedUsd(amount) => ensure_non_negative(amount, label),
+            _ => Ok(()),
+        }
+    }
+
+    /// USD charged on a leg of `stake` that settles with `winnings` of net profit (zero when it loses).
+    pub fn charge(self, stake: Decimal, winnings: Decimal) -> Decimal {
+        match self {
+            FeeModel::OnWinnings(rate) => rate * winnings.max(Decimal::ZERO),
+            FeeModel::OnStake(rate) => rate * stake,
+            FeeModel::Embedded => Decimal::ZERO,
+            FeeModel::FixedUsd(amount) => amount,
+        }
+    }
+
+    /// `(c0, c1)` such that the charge on a leg staked `payout / odds` is `payout * (c0 + c1 / odds)`.
+    fn payout_terms(self, payout: Decimal, wins: bool) -> (Decimal, Decimal) {
+        match self {
+            FeeModel::OnWinnings(rate) if wins => (rate, -rate),
+            FeeModel::OnStake(rate) => (Decimal::ZERO, rate),
+            FeeModel::FixedUsd(amount) => (amount / payout, Decimal::ZERO),
+            FeeModel::OnWinnings(_) | FeeModel::Embedded => (Decimal::ZERO, Decimal::ZERO),
+        }
+    }
 }
 
+/// Stakes are sized so either winning leg returns `payout_usd` at the quoted odds.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct NetMarginInputs {
     pub odds_sx: Decimal,
     pub odds_azuro: Decimal,
-    pub fees_sx: Decimal,
-    pub fees_azuro: Decimal,
-    pub gas_cost: Decimal,
+    pub fee_sx: FeeModel,
+    pub fee_azuro: FeeModel,
+    pub payout_usd: Decimal,
+    pub gas_usd: Decimal,
+    /// Expected odds slippage as a fraction of the payout.
     pub slippage_sx: Decimal,
     pub slippage_azuro: Decimal,
 }
@@ -27,8 +80,22 @@ pub enum NetMarginError {
     InvalidOdds,
     #[error("{0} must be non-negative")]
     NegativeCost(&'static str),
+    #[error("{0} rate must be within [0, 1)")]
+    InvalidFee(&'static str),
+    #[error("payout must be positive")]
+    InvalidPayout,
     #[error("net margin threshold must be within (-1, 1)")]
     InvalidThreshold,
+    #[error(transparent)]
+    Handicap(#[from] HandicapError),
+    #[error("both handicap legs back the same side")]
+    SameHandicapSide,
+    #[error("handicap legs can lose together whatever the stake split")]
+    UnhedgedHandicap,
+    #[error("handicap lines are {0} goals apart, more than {MAX_HANDICAP_GAP}")]
+    HandicapLinesTooFarApart(Decimal),
+    #[error("{0} void probability must be within [0, 1]")]
+    InvalidVoidProbability(&'static str),
 }
 
 impl NetMarginInputs {
@@ -36,13 +103,24 @@ impl NetMarginInputs {
         if self.odds_sx <= Decimal::ONE || self.odds_azuro <= Decimal::ONE {
             return Err(NetMarginError::InvalidOdds);
         }
-        ensure_non_negative(self.fees_sx, "fees_sx")?;
-        ensure_non_negative(self.fees_azuro, "fees_azuro")?;
-        ensure_non_negative(self.gas_cost, "gas_cost")?;
+        if self.payout_usd <= Decimal::ZERO {
+            return Err(NetMarginError::InvalidPayout);
+        }
+        self.fee_sx.validate("fee_sx")?;
+        self.fee_azuro.validate("fee_azuro")?;
+        ensure_non_negative(self.gas_usd, "gas_usd")?;
         ensure_non_negative(self.slippage_sx, "slippage_sx")?;
         ensure_non_negative(self.slippage_azuro, "slippage_azuro")?;
         Ok(())
     }
+
+    /// Fees of the costlier outcome when legs of `stake_sx` / `stake_azuro` return `payouts` (per scenario, SX then Azuro).
+    fn worst_fees(self, stake_sx: Decimal, stake_azuro: Decimal, payouts: impl Iterator<Item = (Decimal, Decimal)>) -> Decimal {
+        payouts
+            .map(|(sx, azuro)| self.fee_sx.charge(stake_sx, sx - stake_sx) + self.fee_azuro.charge(stake_azuro, azuro - stake_azuro))
+            .max()
+            .unwrap_or(Decimal::ZERO)
+    }
 }
 
 fn ensure_non_negative(value: Decimal, label: &'static str) -> Result<(), NetMarginError> {
@@ -52,7 +130,7 @@ fn ensure_non_negative(value: Decimal, label: &'static str) -> Result<(), NetMar
     Ok(())
 }
 
-fn validate_threshold(threshold: Decimal) -> Result<(), NetMarginError> {
+pub(crate) fn validate_threshold(threshold: Decimal) -> Result<(), NetMarginError> {
     if threshold <= -Decimal::ONE || threshold >= Decimal::ONE {
         return Err(NetMarginError::InvalidThreshold);
     }
@@ -66,22 +144,164 @@ pub fn compute_net_margin(inputs: NetMarginInputs) -> Result<NetMarginBreakdown,
     let implied_azuro = Decimal::ONE / inputs.odds_azuro;
     let gross_margin = Decimal::ONE - implied_sx - implied_azuro;
 
-    let fees_total = inputs.fees_sx + inputs.fees_azuro;
-    let slippage_total = inputs.slippage_sx + inputs.slippage_azuro;
-    let gas_total = inputs.gas_cost;
+    let payout = inputs.payout_usd;
+    let (stake_sx, stake_azuro) = (payout * implied_sx, payout * implied_azuro);
+    let scenarios = [(payout, Decimal::ZERO), (Decimal::ZERO, payout)];
+    let fees_total = inputs.worst_fees(stake_sx, stake_azuro, scenarios.into_iter());
+
+    Ok(with_deductions(gross_margin, fees_total, inputs))
+}
+
+fn with_deductions(gross_margin: Decimal, fees_total: Decimal, inputs: NetMarginInputs) -> NetMarginBreakdown {
+    let slippage_total = (inputs.slippage_sx + inputs.slippage_azuro) * inputs.payout_usd;
+    let gas_total = inputs.gas_usd;
 
     let deductions = fees_total + slippage_total + gas_total;
-    let net_margin = gross_margin - deductions;
+    let net_margin = gross_margin - deductions / inputs.payout_usd;
 
-    Ok(NetMarginBreakdown {
+    NetMarginBreakdown {
         gross_margin,
         fees_total,
         slippage_total,
         gas_total,
         net_margin,
+        payout_usd: inputs.payout_usd,
+    }
+}
+
+/// Cross-venue handicap pair; the lines need not mirror each other (e.g. SX -0.25 against Azuro +0.5).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandicapPairInputs {
+    pub bet_sx: HandicapBet,
+    pub bet_azuro: HandicapBet,
+    pub fee_sx: FeeModel,
+    pub fee_azuro: FeeModel,
+    /// Payout guaranteed on the worst scenario.
+    pub payout_usd: Decimal,
+    pub gas_usd: Decimal,
+    pub slippage_sx: Decimal,
+    pub slippage_azuro: Decimal,
+}
+
+impl HandicapPairInputs {
+    fn cost_inputs(self) -> NetMarginInputs {
+        NetMarginInputs {
+            odds_sx: self.bet_sx.odds,
+            odds_azuro: self.bet_azuro.odds,
+            fee_sx: self.fee_sx,
+            fee_azuro: self.fee_azuro,
+            payout_usd: self.payout_usd,
+            gas_usd: self.gas_usd,
+            slippage_sx: self.slippage_sx,
+            slippage_azuro: self.slippage_azuro,
+        }
+    }
+}
+
+/// Payout per unit of total stake when the match ends `goal_difference` (home minus away).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandicapScenario {
+    pub goal_difference: i64,
+    pub payout: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HandicapNetMargin {
+    /// `gross_margin` is the guaranteed one, taken on the worst scenario.
+    pub breakdown: NetMarginBreakdown,
+    /// Share of the total stake on the SX leg that maximises the worst-case payout.
+    pub stake_share_sx: Decimal,
+    pub scenarios: Vec<HandicapScenario>,
+}
+
+/// Settles both legs on every goal difference around the lines and picks the stake split with the best
+/// worst-case payout `R`; the gross margin is `1 - 1/R`, which reduces to `compute_net_margin` for mirrored
+/// whole or half lines.
+pub fn compute_handicap_net_margin(inputs: HandicapPairInputs) -> Result<HandicapNetMargin, NetMarginError> {
+    let costs = inputs.cost_inputs();
+    costs.validate()?;
+    if inputs.bet_sx.side == inputs.bet_azuro.side {
+        return Err(NetMarginError::SameHandicapSide);
+    }
+
+    let legs: Vec<(i64, Decimal, Decimal)> = goal_differences(&inputs.bet_sx, &inputs.bet_azuro)?
+        .map(|difference| {
+            let sx = inputs.bet_sx.settle_difference(difference).payout(inputs.bet_sx.odds);
+            let azuro = inputs.bet_azuro.settle_difference(difference).payout(inputs.bet_azuro.odds);
+            (difference, sx, azuro)
+        })
+        .collect();
+    let worst_payout = |share: Decimal| {
+        legs.iter()
+            .map(|(_, sx, azuro)| share * sx + (Decimal::ONE - share) * azuro)
+            .min()
+            .unwrap_or(Decimal::ZERO)
+    };
+
+    // The worst case is concave and piecewise linear in the share, so its maximum sits on a bound
+    // or where two scenario payouts cross.
+    let mut candidates = vec![Decimal::ZERO, Decimal::ONE];
+    for (index, (_, sx_a, azuro_a)) in legs.iter().enumerate() {
+        for (_, sx_b, azuro_b) in &legs[index + 1..] {
+            let slope = (sx_a - azuro_a) - (sx_b - azuro_b);
+            if slope.is_zero() {
+                continue;
+            }
+            let share = (azuro_b - azuro_a) / slope;
+            if share > Decimal::ZERO && share < Decimal::ONE {
+                candidates.push(share);
+            }
+        }
+    }
+    let (stake_share_sx, guaranteed) = candidates
+        .into_iter()
+        .map(|share| (share, worst_payout(share)))
+        .fold((Decimal::ZERO, Decimal::MIN), |best, candidate| if candidate.1 > best.1 { candidate } else { best });
+    if guaranteed <= Decimal::ZERO {
+        return Err(NetMarginError::UnhedgedHandicap);
+    }
+
+    let total_stake = inputs.payout_usd / guaranteed;
+    let (stake_sx, stake_azuro) = (stake_share_sx * total_stake, (Decimal::ONE - stake_share_sx) * total_stake);
+    let settled = legs.iter().map(|(_, sx, azuro)| (stake_sx * sx, stake_azuro * azuro));
+    let fees_total = costs.worst_fees(stake_sx, stake_azuro, settled);
+
+    let scenarios = legs
+        .iter()
+        .map(|&(goal_difference, sx, azuro)| HandicapScenario {
+            goal_difference,
+            payout: stake_share_sx * sx + (Decimal::ONE - stake_share_sx) * azuro,
+        })
+        .collect();
+    Ok(HandicapNetMargin {
+        breakdown: with_deductions(Decimal::ONE - Decimal::ONE / guaranteed, fees_total, costs),
+        stake_share_sx,
+        scenarios,
     })
 }
 
+/// Widest gap, in goals, between the two legs' pivots; further apart the pair is not one market.
+const MAX_HANDICAP_GAP: i64 = 4;
+
+/// Every home-minus-away difference at which a leg can settle differently, plus one goal either side.
+fn goal_differences(left: &HandicapBet, right: &HandicapBet) -> Result<std::ops::RangeInclusive<i64>, NetMarginError> {
+    let pivot = |bet: &HandicapBet| match bet.side {
+        HandicapSide::Home => -bet.line.value(),
+        HandicapSide::Away => bet.line.value(),
+    };
+    let (low, high) = {
+        let (a, b) = (pivot(left), pivot(right));
+        (a.min(b), a.max(b))
+    };
+    let gap = high - low;
+    if gap > Decimal::from(MAX_HANDICAP_GAP) {
+        return Err(NetMarginError::HandicapLinesTooFarApart(gap));
+    }
+    let low = i64::try_from(low.floor()).map_err(|_| HandicapError::InvalidLine(low))?;
+    let high = i64::try_from(high.ceil()).map_err(|_| HandicapError::InvalidLine(high))?;
+    Ok(low - 1..=high + 1)
+}
+
 pub fn meets_net_margin_threshold(
     inputs: NetMarginInputs,
     threshold: Decimal,
@@ -92,9 +312,167 @@ pub fn meets_net_margin_threshold(
     Ok((breakdown, meets_threshold))
 }
 
+/// How far an opportunity is from `threshold`; margins are fractions of the guaranteed payout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarginSensitivity {
+    pub breakdown: NetMarginBreakdown,
+    pub threshold: Decimal,
+    /// `net_margin - threshold`; negative once breached.
+    pub headroom: Decimal,
+    /// Lowest SX odds that still meet the threshold at the current Azuro odds, fees recomputed at those
+    /// odds; `None` if none do.
+    pub break_even_odds_sx: Option<Decimal>,
+    pub break_even_odds_azuro: Option<Decimal>,
+    /// Total slippage (both legs, USD) tolerable before the threshold is breached.
+    pub max_slippage_total: Decimal,
+    pub max_gas_usd: Decimal,
+    /// ∂net_margin/∂odds_sx on the costlier scenario: `(1 - r)/odds_sx²` when SX charges `r` on winnings and
+    /// wins there, `(1 + r)/odds_sx²` for a stake fee, `1/odds_sx²` otherwise.
+    pub d_net_d_odds_sx: Decimal,
+    pub d_net_d_odds_azuro: Decimal,
+    /// Fees, gas and slippage all enter linearly: each USD of cost moves the margin by `-1/payout_usd`.
+    pub d_net_d_cost: Decimal,
+}
+
+pub fn margin_sensitivity(inputs: NetMarginInputs, threshold: Decimal) -> Result<MarginSensitivity, NetMarginError> {
+    validate_threshold(threshold)?;
+    let breakdown = compute_net_margin(inputs)?;
+    let headroom = breakdown.net_margin - threshold;
+    let payout = inputs.payout_usd;
+    let costs = (breakdown.slippage_total + breakdown.gas_total) / payout;
+    // Fee terms of (SX, Azuro) when SX wins, then when Azuro wins.
+    let scenarios = [true, false].map(|sx_wins| (inputs.fee_sx.payout_terms(payout, sx_wins), inputs.fee_azuro.payout_terms(payout, !sx_wins)));
+    // Each scenario's margin is `a - b/o` in the leg's odds `o`; the threshold holds once it does on every scenario.
+    let break_even = |sx_leg: bool, other_odds: Decimal| {
+        scenarios.iter().try_fold(Decimal::ZERO, |lowest, &(sx, azuro)| {
+            let ((c0, c1), (other_c0, other_c1)) = if sx_leg { (sx, azuro) } else { (azuro, sx) };
+            let a = Decimal::ONE - Decimal::ONE / other_odds - other_c0 - other_c1 / other_odds - c0 - costs - threshold;
+            (a > Decimal::ZERO).then(|| lowest.max((Decimal::ONE + c1) / a))
+        })
+    };
+    let fee_share = |((sx_c0, sx_c1), (azuro_c0, azuro_c1)): &Terms| {
+        sx_c0 + sx_c1 / inputs.odds_sx + azuro_c0 + azuro_c1 / inputs.odds_azuro
+    };
+    let costlier = if fee_share(&scenarios[0]) >= fee_share(&scenarios[1]) { scenarios[0] } else { scenarios[1] };
+    let ((_, sx_c1), (_, azuro_c1)) = costlier;
+
+    Ok(MarginSensitivity {
+        breakdown,
+        threshold,
+        headroom,
+        break_even_odds_sx: break_even(true, inputs.odds_azuro),
+        break_even_odds_azuro: break_even(false, inputs.odds_sx),
+        max_slippage_total: breakdown.slippage_total + headroom * breakdown.payout_usd,
+        max_gas_usd: breakdown.gas_total + headroom * breakdown.payout_usd,
+        d_net_d_odds_sx: (Decimal::ONE + sx_c1) / (inputs.odds_sx * inputs.odds_sx),
+        d_net_d_odds_azuro: (Decimal::ONE + azuro_c1) / (inputs.odds_azuro * inputs.odds_azuro),
+        d_net_d_cost: -Decimal::ONE / breakdown.payout_usd,
+    })
+}
+
+/// `FeeModel::payout_terms` of the SX and Azuro legs in one settlement scenario.
+type Terms = ((Decimal, Decimal), (Decimal, Decimal));
+
+/// Chance that one leg is voided (postponement, venue rule) while the trade is open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LegVoidRisk {
+    pub void_probability: Decimal,
+    /// The leg settles under rules the other venue does not share (overtime, postponement window…),
+    /// so it can void on its own.
+    pub rule_mismatch: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VoidRisk {
+    pub sx: LegVoidRisk,
+    pub azuro: LegVoidRisk,
+}
+
+impl VoidRisk {
+    fn validate(self) -> Result<(), NetMarginError> {
+        for (probability, leg) in [(self.sx.void_probability, "sx"), (self.azuro.void_probability, "azuro")] {
+            if probability < Decimal::ZERO || probability > Decimal::ONE {
+                return Err(NetMarginError::InvalidVoidProbability(leg));
+            }
+        }
+        Ok(())
+    }
+
+    /// `(both void, only SX voids, only Azuro voids)`. Without a rule mismatch both venues void on the same
+    /// event, so voids overlap as much as possible; with one they are independent.
+    fn scenario_probabilities(self) -> (Decimal, Decimal, Decimal) {
+        let (sx, azuro) = (self.sx.void_probability, self.azuro.void_probability);
+        let both = if self.sx.rule_mismatch || self.azuro.rule_mismatch { sx * azuro } else { sx.min(azuro) };
+        (both, sx - both, azuro - both)
+    }
+}
+
+/// Margins are fractions of the guaranteed payout, like `NetMarginBreakdown`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VoidAdjustedMargin {
+    pub breakdown: NetMarginBreakdown,
+    /// Probability that exactly one leg voids and leaves the other naked.
+    pub naked_probability: Decimal,
+    /// Probability-weighted margin; a naked leg is valued at the pair's overround-free odds.
+    pub expected_margin: Decimal,
+    /// Loss when one leg voids and the naked leg loses, as a positive fraction.
+    pub worst_case_loss: Decimal,
+    /// Risk premium `naked_probability × worst_case_loss`; `meets_void_adjusted_threshold` adds the threshold.
+    pub required_net_margin: Decimal,
+}
+
+pub fn compute_void_adjusted_margin(inputs: NetMarginInputs, risk: VoidRisk) -> Result<VoidAdjustedMargin, NetMarginError> {
+    risk.validate()?;
+    let breakdown = compute_net_margin(inputs)?;
+    let stake_sx = Decimal::ONE / inputs.odds_sx;
+    let stake_azuro = Decimal::ONE / inputs.odds_azuro;
+    let fair_sx = stake_sx / (stake_sx + stake_azuro);
+    let sunk = (breakdown.fees_total + breakdown.gas_total) / breakdown.payout_usd;
+
+    // Naked SX leg once Azuro refunds: wins the full payout with its fair probability.
+    let naked_sx = fair_sx - stake_sx - sunk;
+    let naked_azuro = (Decimal::ONE - fair_sx) - stake_azuro - sunk;
+    let (both, only_sx, only_azuro) = risk.scenario_probabilities();
+    let settled = Decimal::ONE - both - only_sx - only_azuro;
+    let expected_margin = settled * breakdown.net_margin - both * sunk + only_sx * naked_azuro + only_azuro * naked_sx;
+
+    let mut worst_case_loss = (-breakdown.net_margin).max(Decimal::ZERO);
+    if both > Decimal::ZERO {
+        worst_case_loss = worst_case_loss.max(sunk);
+    }
+    if only_sx > Decimal::ZERO {
+        worst_case_loss = worst_case_loss.max(stake_azuro + sunk);
+    }
+    if only_azuro > Decimal::ZERO {
+        worst_case_loss = worst_case_loss.max(stake_sx + sunk);
+    }
+
+    Ok(VoidAdjustedMargin {
+        breakdown,
+        naked_probability: only_sx + only_azuro,
+        expected_margin,
+        worst_case_loss,
+        required_net_margin: (only_sx + only_azuro) * worst_case_loss,
+    })
+}
+
+/// Like `meets_net_margin_threshold`, but riskier pairs must clear `threshold` plus their naked-leg exposure.
+pub fn meets_void_adjusted_threshold(
+    inputs: NetMarginInputs,
+    risk: VoidRisk,
+    threshold: Decimal,
+) -> Result<(VoidAdjustedMargin, bool), NetMarginError> {
+    validate_threshold(threshold)?;
+    let mut adjusted = compute_void_adjusted_margin(inputs, risk)?;
+    adjusted.required_net_margin += threshold;
+    let meets_threshold = adjusted.breakdown.net_margin >= adjusted.required_net_margin;
+    Ok((adjusted, meets_threshold))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::handicap::HandicapLine;
     use std::str::FromStr;
 
     fn dec(value: &str) -> Decimal {
@@ -106,9 +484,10 @@ mod tests {
         let inputs = NetMarginInputs {
             odds_sx: dec("1.0"),
             odds_azuro: dec("1.5"),
-            fees_sx: Decimal::ZERO,
-            fees_azuro: Decimal::ZERO,
-            gas_cost: Decimal::ZERO,
+            fee_sx: FeeModel::Embedded,
+            fee_azuro: FeeModel::Embedded,
+            payout_usd: dec("100"),
+            gas_usd: Decimal::ZERO,
             slippage_sx: Decimal::ZERO,
             slippage_azuro: Decimal::ZERO,
         };
@@ -123,16 +502,22 @@ mod tests {
         let inputs = NetMarginInputs {
             odds_sx: dec("2.1"),
             odds_azuro: dec("2.2"),
-            fees_sx: Decimal::NEGATIVE_ONE,
-            fees_azuro: Decimal::ZERO,
-            gas_cost: Decimal::ZERO,
+            fee_sx: FeeModel::FixedUsd(Decimal::NEGATIVE_ONE),
+            fee_azuro: FeeModel::Embedded,
+            payout_usd: dec("100"),
+            gas_usd: Decimal::ZERO,
             slippage_sx: Decimal::ZERO,
             slippage_azuro: Decimal::ZERO,
         };
         assert_eq!(
             compute_net_margin(inputs).unwrap_err(),
-            NetMarginError::NegativeCost("fees_sx")
+            NetMarginError::NegativeCost("fee_sx")
         );
+
+        let invalid_rate = NetMarginInputs { fee_sx: FeeModel::OnWinnings(Decimal::ONE), ..inputs };
+        assert_eq!(compute_net_margin(invalid_rate).unwrap_err(), NetMarginError::InvalidFee("fee_sx"));
+        let no_payout = NetMarginInputs { fee_sx: FeeModel::Embedded, payout_usd: Decimal::ZERO, ..inputs };
+        assert_eq!(compute_net_margin(no_payout).unwrap_err(), NetMarginError::InvalidPayout);
     }
 
     #[test]
@@ -140,9 +525,10 @@ mod tests {
         let inputs = NetMarginInputs {
             odds_sx: dec("2.05"),
             odds_azuro: dec("2.15"),
-            fees_sx: dec("0.0025"),
-            fees_azuro: dec("0.003"),
-            gas_cost: dec("0.0007"),
+            fee_sx: FeeModel::FixedUsd(dec("0.25")),
+            fee_azuro: FeeModel::FixedUsd(dec("0.30")),
+            payout_usd: dec("100"),
+            gas_usd: dec("0.07"),
             slippage_sx: dec("0.0012"),
             slippage_azuro: dec("0.0009"),
         };
@@ -150,22 +536,47 @@ mod tests {
         let breakdown = compute_net_margin(inputs).expect("net margin");
 
         assert!(breakdown.gross_margin > Decimal::ZERO);
-        assert_eq!(breakdown.fees_total, dec("0.0055"));
-        assert_eq!(breakdown.slippage_total, dec("0.0021"));
-        assert_eq!(breakdown.gas_total, dec("0.0007"));
+        assert_eq!(breakdown.fees_total, dec("0.55"));
+        assert_eq!(breakdown.slippage_total, dec("0.21"));
+        assert_eq!(breakdown.gas_total, dec("0.07"));
 
         let expected_net = breakdown.gross_margin - dec("0.0083");
         assert!((breakdown.net_margin - expected_net).abs() < Decimal::new(1, 6));
     }
 
+    #[test]
+    fn fee_models_are_charged_in_usd_per_scenario() {
+        let inputs = NetMarginInputs {
+            odds_sx: dec("2.10"),
+            odds_azuro: dec("2.00"),
+            fee_sx: FeeModel::OnWinnings(dec("0.02")),
+            fee_azuro: FeeModel::Embedded,
+            payout_usd: dec("210"),
+            gas_usd: Decimal::ZERO,
+            slippage_sx: Decimal::ZERO,
+            slippage_azuro: Decimal::ZERO,
+        };
+        let breakdown = compute_net_margin(inputs).expect("net margin");
+
+        // SX wins: 100 staked at 2.10, 2 % of the 110 winnings.
+        assert_eq!(breakdown.fees_total, dec("2.2"));
+        let effective = crate::odds_converter::decimal_after_commission(dec("2.10"), dec("0.02")).expect("commission");
+        let profit_if_sx_wins = dec("100") * effective - dec("100") - dec("105");
+        assert_eq!((breakdown.net_margin * breakdown.payout_usd).round_dp(10), profit_if_sx_wins);
+
+        let on_stake = NetMarginInputs { fee_sx: FeeModel::OnStake(dec("0.01")), fee_azuro: FeeModel::OnStake(dec("0.01")), ..inputs };
+        assert_eq!(compute_net_margin(on_stake).expect("net margin").fees_total, dec("2.05"));
+    }
+
     #[test]
     fn validates_threshold_and_reports_decision() {
         let inputs = NetMarginInputs {
             odds_sx: dec("2.25"),
             odds_azuro: dec("2.40"),
-            fees_sx: dec("0.0020"),
-            fees_azuro: dec("0.0020"),
-            gas_cost: dec("0.0012"),
+            fee_sx: FeeModel::FixedUsd(dec("0.20")),
+            fee_azuro: FeeModel::FixedUsd(dec("0.20")),
+            payout_usd: dec("100"),
+            gas_usd: dec("0.12"),
             slippage_sx: dec("0.0010"),
             slippage_azuro: dec("0.0011"),
         };
@@ -181,4 +592,166 @@ mod tests {
             NetMarginError::InvalidThreshold
         );
     }
+
+    fn handicap_inputs(sx: (HandicapSide, &str, &str), azuro: (HandicapSide, &str, &str)) -> HandicapPairInputs {
+        let bet = |(side, line, odds): (HandicapSide, &str, &str)| HandicapBet {
+            side,
+            line: HandicapLine::new(dec(line)).expect("valid line"),
+            odds: dec(odds),
+        };
+        HandicapPairInputs {
+            bet_sx: bet(sx),
+            bet_azuro: bet(azuro),
+            fee_sx: FeeModel::FixedUsd(dec("0.2")),
+            fee_azuro: FeeModel::FixedUsd(dec("0.2")),
+            payout_usd: dec("100"),
+            gas_usd: dec("0.1"),
+            slippage_sx: Decimal::ZERO,
+            slippage_azuro: Decimal::ZERO,
+        }
+    }
+
+    #[test]
+    fn mirrored_handicap_lines_match_the_two_way_formula() {
+        let inputs = handicap_inputs((HandicapSide::Home, "-0.5", "2.10"), (HandicapSide::Away, "0.5", "2.00"));
+        let handicap = compute_handicap_net_margin(inputs).expect("handicap margin");
+        let plain = compute_net_margin(inputs.cost_inputs()).expect("net margin");
+
+        assert!((handicap.breakdown.net_margin - plain.net_margin).abs() < Decimal::new(1, 12));
+        assert!((handicap.stake_share_sx - dec("2.00") / dec("4.10")).abs() < Decimal::new(1, 12));
+    }
+
+    #[test]
+    fn quarter_line_against_half_line_keeps_the_draw_upside() {
+        let inputs = handicap_inputs((HandicapSide::Home, "-0.25", "2.10"), (HandicapSide::Away, "0.5", "2.00"));
+        let result = compute_handicap_net_margin(inputs).expect("handicap margin");
+
+        // Same worst case as the mirrored pair; a draw half-refunds SX while Azuro pays in full.
+        assert!((result.breakdown.gross_margin - (Decimal::ONE - Decimal::ONE / dec("2.10") - dec("0.5"))).abs() < Decimal::new(1, 12));
+        let draw = result.scenarios.iter().find(|scenario| scenario.goal_difference == 0).expect("draw scenario");
+        let worst = result.scenarios.iter().map(|scenario| scenario.payout).min().unwrap();
+        assert!(draw.payout > worst);
+
+        let uncovered = handicap_inputs((HandicapSide::Home, "-1.5", "2.10"), (HandicapSide::Away, "0.5", "2.00"));
+        assert_eq!(compute_handicap_net_margin(uncovered).unwrap_err(), NetMarginError::UnhedgedHandicap);
+        let same_side = handicap_inputs((HandicapSide::Home, "-0.25", "2.10"), (HandicapSide::Home, "0.25", "2.00"));
+        assert_eq!(compute_handicap_net_margin(same_side).unwrap_err(), NetMarginError::SameHandicapSide);
+    }
+
+    #[test]
+    fn handicap_lines_far_apart_are_rejected_before_scanning() {
+        let far = handicap_inputs((HandicapSide::Home, "-1000", "2.10"), (HandicapSide::Away, "0.5", "2.00"));
+        assert_eq!(compute_handicap_net_margin(far).unwrap_err(), NetMarginError::HandicapLinesTooFarApart(dec("999.5")));
+
+        let mirrored = handicap_inputs((HandicapSide::Home, "-1000", "2.10"), (HandicapSide::Away, "1000", "2.00"));
+        assert_eq!(compute_handicap_net_margin(mirrored).expect("handicap margin").scenarios.len(), 3);
+    }
+
+    fn void_inputs() -> NetMarginInputs {
+        NetMarginInputs {
+            odds_sx: dec("2.10"),
+            odds_azuro: dec("2.10"),
+            fee_sx: FeeModel::FixedUsd(dec("0.2")),
+            fee_azuro: FeeModel::FixedUsd(dec("0.2")),
+            payout_usd: dec("100"),
+            gas_usd: dec("0.1"),
+            slippage_sx: Decimal::ZERO,
+            slippage_azuro: Decimal::ZERO,
+        }
+    }
+
+    fn leg_risk(void_probability: &str, rule_mismatch: bool) -> LegVoidRisk {
+        LegVoidRisk { void_probability: dec(void_probability), rule_mismatch }
+    }
+
+    #[test]
+    fn shared_voids_leave_no_naked_exposure() {
+        let risk = VoidRisk { sx: leg_risk("0.02", false), azuro: leg_risk("0.02", false) };
+        let adjusted = compute_void_adjusted_margin(void_inputs(), risk).expect("void adjusted margin");
+
+        assert_eq!(adjusted.naked_probability, Decimal::ZERO);
+        assert_eq!(adjusted.required_net_margin, Decimal::ZERO);
+        assert_eq!(adjusted.worst_case_loss, dec("0.005"));
+        let expected = dec("0.98") * adjusted.breakdown.net_margin - dec("0.02") * dec("0.005");
+        assert_eq!(adjusted.expected_margin, expected);
+
+        let none = compute_void_adjusted_margin(void_inputs(), VoidRisk::default()).expect("void adjusted margin");
+        assert_eq!(none.expected_margin, none.breakdown.net_margin);
+    }
+
+    #[test]
+    fn rule_mismatch_raises_the_required_margin() {
+        let threshold = dec("0.015");
+        let (safe, safe_meets) = meets_void_adjusted_threshold(void_inputs(), VoidRisk::default(), threshold).expect("threshold");
+        assert!(safe_meets);
+        assert_eq!(safe.required_net_margin, threshold);
+
+        let risk = VoidRisk { sx: leg_risk("0.05", true), azuro: leg_risk("0.01", false) };
+        let (risky, risky_meets) = meets_void_adjusted_threshold(void_inputs(), risk, threshold).expect("threshold");
+        assert_eq!(risky.naked_probability, dec("0.05") + dec("0.01") - dec("2") * dec("0.0005"));
+        assert!(risky.worst_case_loss > dec("0.47"));
+        assert!(risky.required_net_margin > dec("0.04"));
+        assert!(!risky_meets);
+        assert!(risky.expected_margin < risky.breakdown.net_margin);
+
+        let invalid = VoidRisk { sx: leg_risk("1.5", false), azuro: LegVoidRisk::default() };
+        assert_eq!(compute_void_adjusted_margin(void_inputs(), invalid).unwrap_err(), NetMarginError::InvalidVoidProbability("sx"));
+    }
+
+    #[test]
+    fn sensitivity_solves_break_even_and_headroom() {
+        let inputs = void_inputs();
+        let threshold = dec("0.015");
+        let sensitivity = margin_sensitivity(inputs, threshold).expect("sensitivity");
+        let tolerance = Decimal::new(1, 20);
+
+        assert_eq!(sensitivity.headroom, sensitivity.breakdown.net_margin - threshold);
+        let break_even = sensitivity.break_even_odds_sx.expect("reachable");
+        assert!(break_even < inputs.odds_sx);
+        let at_break_even = compute_net_margin(NetMarginInputs { odds_sx: break_even, ..inputs }).expect("net margin");
+        assert!((at_break_even.net_margin - threshold).abs() < tolerance);
+
+        let at_max_gas = compute_net_margin(NetMarginInputs { gas_usd: sensitivity.max_gas_usd, ..inputs }).expect("net margin");
+        assert!((at_max_gas.net_margin - threshold).abs() < tolerance);
+        assert_eq!(sensitivity.max_slippage_total, sensitivity.headroom * inputs.payout_usd);
+
+        let bumped = compute_net_margin(NetMarginInputs { odds_sx: inputs.odds_sx + dec("0.0001"), ..inputs }).expect("net margin");
+        let slope = (bumped.net_margin - sensitivity.breakdown.net_margin) / dec("0.0001");
+        assert!((slope - sensitivity.d_net_d_odds_sx).abs() < dec("0.0001"));
+
+        let hopeless = NetMarginInputs { odds_azuro: dec("1.01"), ..inputs };
+        assert_eq!(margin_sensitivity(hopeless, threshold).expect("sensitivity").break_even_odds_sx, None);
+    }
+
+    #[test]
+    fn sensitivity_accounts_for_odds_dependent_fees() {
+        let inputs = NetMarginInputs {
+            odds_sx: dec("2.2"),
+            odds_azuro: dec("2.05"),
+            fee_sx: FeeModel::OnWinnings(dec("0.02")),
+            fee_azuro: FeeModel::OnStake(dec("0.01")),
+            ..void_inputs()
+        };
+        let threshold = dec("0.015");
+        let sensitivity = margin_sensitivity(inputs, threshold).expect("sensitivity");
+        let tolerance = Decimal::new(1, 20);
+
+        assert_eq!(sensitivity.d_net_d_odds_sx, dec("0.98") / (inputs.odds_sx * inputs.odds_sx));
+        assert_eq!(sensitivity.d_net_d_odds_azuro, dec("1.01") / (inputs.odds_azuro * inputs.odds_azuro));
+        let step = dec("0.0001");
+        for (bumped, slope) in [
+            (NetMarginInputs { odds_sx: inputs.odds_sx + step, ..inputs }, sensitivity.d_net_d_odds_sx),
+            (NetMarginInputs { odds_azuro: inputs.odds_azuro + step, ..inputs }, sensitivity.d_net_d_odds_azuro),
+        ] {
+            let moved = compute_net_margin(bumped).expect("net margin").net_margin - sensitivity.breakdown.net_margin;
+            assert!((moved / step - slope).abs() < dec("0.0001"));
+        }
+
+        let break_even_sx = sensitivity.break_even_odds_sx.expect("reachable");
+        let at_sx = compute_net_margin(NetMarginInputs { odds_sx: break_even_sx, ..inputs }).expect("net margin");
+        assert!((at_sx.net_margin - threshold).abs() < tolerance);
+        let break_even_azuro = sensitivity.break_even_odds_azuro.expect("reachable");
+        let at_azuro = compute_net_margin(NetMarginInputs { odds_azuro: break_even_azuro, ..inputs }).expect("net margin");
+        assert!((at_azuro.net_margin - threshold).abs() < tolerance);
+    }
 }
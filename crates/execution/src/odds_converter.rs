@@ -25,7 +25,7 @@ fn validate_decimal(decimal_odds: Decimal) -> Result<(), ConversionError> {
     Ok(())
 }
 
-fn validate_commission(rate: Decimal) -> Result<(), ConversionError> {
+pub(crate) fn validate_commission(rate: Decimal) -> Result<(), ConversionError> {
     if rate < Decimal::ZERO || rate >= Decimal::ONE {
         return Err(ConversionError::InvalidCommission);
     }
@@ -0,0 +1,212 @@
+use rust_decimal::prelude::*;
+use rust_decimal::Decimal;
+
+use crate::net_margin::{meets_net_margin_threshold, validate_threshold, FeeModel, NetMarginBreakdown, NetMarginError, NetMarginInputs};
+use crate::odds_converter::ConversionError;
+
+/// Slack granted to the f64 screen so rounding never rejects a pair the exact path accepts; the
+/// f64 error on these formulas stays below 1e-12 for odds under 1e6.
+pub const SCREEN_TOLERANCE: f64 = 1e-9;
+
+/// f64 mirror of `FeeModel`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FastFeeModel {
+    OnWinnings(f64),
+    OnStake(f64),
+    Embedded,
+    FixedUsd(f64),
+}
+
+impl FastFeeModel {
+    pub fn to_decimal(self) -> Option<FeeModel> {
+        Some(match self {
+            FastFeeModel::OnWinnings(rate) => FeeModel::OnWinnings(Decimal::from_f64(rate)?),
+            FastFeeModel::OnStake(rate) => FeeModel::OnStake(Decimal::from_f64(rate)?),
+            FastFeeModel::Embedded => FeeModel::Embedded,
+            FastFeeModel::FixedUsd(amount) => FeeModel::FixedUsd(Decimal::from_f64(amount)?),
+        })
+    }
+
+    fn is_valid(self) -> bool {
+        match self {
+            FastFeeModel::OnWinnings(rate) | FastFeeModel::OnStake(rate) => (0.0..1.0).contains(&rate),
+            FastFeeModel::Embedded => true,
+            FastFeeModel::FixedUsd(amount) => amount.is_finite() && amount >= 0.0,
+        }
+    }
+
+    fn charge(self, stake: f64, winnings: f64) -> f64 {
+        match self {
+            FastFeeModel::OnWinnings(rate) => rate * winnings.max(0.0),
+            FastFeeModel::OnStake(rate) => rate * stake,
+            FastFeeModel::Embedded => 0.0,
+            FastFeeModel::FixedUsd(amount) => amount,
+        }
+    }
+}
+
+/// f64 mirror of `NetMarginInputs`, as quoted by `sx_client` / `azuro_client`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FastNetMarginInputs {
+    pub odds_sx: f64,
+    pub odds_azuro: f64,
+    pub fee_sx: FastFeeModel,
+    pub fee_azuro: FastFeeModel,
+    pub payout_usd: f64,
+    pub gas_usd: f64,
+    pub slippage_sx: f64,
+    pub slippage_azuro: f64,
+}
+
+impl FastNetMarginInputs {
+    /// `None` when a field is not finite.
+    pub fn to_decimal(self) -> Option<NetMarginInputs> {
+        Some(NetMarginInputs {
+            odds_sx: Decimal::from_f64(self.odds_sx)?,
+            odds_azuro: Decimal::from_f64(self.odds_azuro)?,
+            fee_sx: self.fee_sx.to_decimal()?,
+            fee_azuro: self.fee_azuro.to_decimal()?,
+            payout_usd: Decimal::from_f64(self.payout_usd)?,
+            gas_usd: Decimal::from_f64(self.gas_usd)?,
+            slippage_sx: Decimal::from_f64(self.slippage_sx)?,
+            slippage_azuro: Decimal::from_f64(self.slippage_azuro)?,
+        })
+    }
+
+    fn is_valid(self) -> bool {
+        let costs = [self.gas_usd, self.slippage_sx, self.slippage_azuro];
+        self.odds_sx > 1.0 && self.odds_azuro > 1.0 && self.odds_sx.is_finite() && self.odds_azuro.is_finite()
+            && self.payout_usd > 0.0 && self.payout_usd.is_finite()
+            && self.fee_sx.is_valid() && self.fee_azuro.is_valid()
+            && costs.iter().all(|cost| cost.is_finite() && *cost >= 0.0)
+    }
+}
+
+/// `compute_net_margin` in f64; `None` where the exact path would return an error.
+pub fn fast_net_margin(inputs: FastNetMarginInputs) -> Option<f64> {
+    if !inputs.is_valid() {
+        return None;
+    }
+    let payout = inputs.payout_usd;
+    let (stake_sx, stake_azuro) = (payout / inputs.odds_sx, payout / inputs.odds_azuro);
+    let gross = 1.0 - 1.0 / inputs.odds_sx - 1.0 / inputs.odds_azuro;
+    let fees_if_sx_wins = inputs.fee_sx.charge(stake_sx, payout - stake_sx) + inputs.fee_azuro.charge(stake_azuro, -stake_azuro);
+    let fees_if_azuro_wins = inputs.fee_sx.charge(stake_sx, -stake_sx) + inputs.fee_azuro.charge(stake_azuro, payout - stake_azuro);
+    let deductions = fees_if_sx_wins.max(fees_if_azuro_wins) + (inputs.slippage_sx + inputs.slippage_azuro) * payout + inputs.gas_usd;
+    Some(gross - deductions / payout)
+}
+
+/// Cheap pre-filter: `false` only when the pair is certainly below `threshold`.
+pub fn passes_screen(inputs: FastNetMarginInputs, threshold: f64) -> bool {
+    fast_net_margin(inputs).is_some_and(|net| net >= threshold - SCREEN_TOLERANCE)
+}
+
+/// Screens every candidate in f64, then re-checks the survivors in `Decimal`. Returns the index and
+/// exact breakdown of each accepted candidate.
+pub fn screen_and_verify(
+    candidates: &[FastNetMarginInputs],
+    threshold: Decimal,
+) -> Result<Vec<(usize, NetMarginBreakdown)>, NetMarginError> {
+    validate_threshold(threshold)?;
+    let fast_threshold = threshold.to_f64().unwrap_or(f64::INFINITY);
+    let mut accepted = Vec::new();
+    for (index, candidate) in candidates.iter().enumerate() {
+        if !passes_screen(*candidate, fast_threshold) {
+            continue;
+        }
+        let Some(exact) = candidate.to_decimal() else {
+            continue;
+        };
+        match meets_net_margin_threshold(exact, threshold) {
+            Ok((breakdown, true)) => accepted.push((index, breakdown)),
+            Ok((_, false)) | Err(NetMarginError::InvalidOdds | NetMarginError::NegativeCost(_) | NetMarginError::InvalidFee(_) | NetMarginError::InvalidPayout) => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(accepted)
+}
+
+/// `normalized_probabilities` in f64, with the same validation; verify survivors with the `Decimal` version.
+pub fn fast_normalized_probabilities(decimals: &[f64]) -> Result<Vec<f64>, ConversionError> {
+    if decimals.iter().any(|odds| !(*odds > 1.0 && odds.is_finite())) {
+        return Err(ConversionError::InvalidDecimal);
+    }
+    let total: f64 = decimals.iter().map(|odds| 1.0 / odds).sum();
+    if total <= 0.0 {
+        return Err(ConversionError::InvalidProbabilityTotal);
+    }
+    Ok(decimals.iter().map(|odds| (1.0 / odds) / total).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net_margin::compute_net_margin;
+    use crate::odds_converter::normalized_probabilities;
+    use proptest::prelude::*;
+
+    fn inputs(odds_sx: f64, odds_azuro: f64, costs: f64) -> FastNetMarginInputs {
+        FastNetMarginInputs {
+            odds_sx,
+            odds_azuro,
+            fee_sx: FastFeeModel::OnWinnings(costs),
+            fee_azuro: FastFeeModel::Embedded,
+            payout_usd: 100.0,
+            gas_usd: costs * 100.0,
+            slippage_sx: costs,
+            slippage_azuro: costs,
+        }
+    }
+
+    #[test]
+    fn screen_then_verify_keeps_only_exact_accepts() {
+        let candidates = [inputs(2.25, 2.40, 0.001), inputs(1.9, 1.9, 0.001), inputs(1.0, 3.0, 0.0), inputs(2.3, 2.3, f64::NAN)];
+        let accepted = screen_and_verify(&candidates, Decimal::new(15, 3)).expect("screening");
+        assert_eq!(accepted.len(), 1);
+        assert_eq!(accepted[0].0, 0);
+        assert_eq!(accepted[0].1, compute_net_margin(candidates[0].to_decimal().unwrap()).unwrap());
+
+        assert_eq!(screen_and_verify(&candidates, Decimal::new(12, 1)).unwrap_err(), NetMarginError::InvalidThreshold);
+    }
+
+    #[test]
+    fn fast_probabilities_match_exact_ones() {
+        let fast = fast_normalized_probabilities(&[1.85, 2.05]).expect("fast");
+        let exact = normalized_probabilities(&[Decimal::new(185, 2), Decimal::new(205, 2)]).expect("exact");
+        for (fast, exact) in fast.iter().zip(exact) {
+            assert!((fast - exact.to_f64().unwrap()).abs() < 1e-12);
+        }
+        assert_eq!(fast_normalized_probabilities(&[1.0, 2.0]), Err(ConversionError::InvalidDecimal));
+    }
+
+    fn fee_model() -> impl Strategy<Value = FastFeeModel> {
+        prop_oneof![
+            (0.0f64..0.1).prop_map(FastFeeModel::OnWinnings),
+            (0.0f64..0.1).prop_map(FastFeeModel::OnStake),
+            Just(FastFeeModel::Embedded),
+            (0.0f64..2.0).prop_map(FastFeeModel::FixedUsd),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn screen_never_rejects_an_exact_accept(
+            odds_sx in 1.001f64..50.0,
+            odds_azuro in 1.001f64..50.0,
+            fee_sx in fee_model(),
+            fee_azuro in fee_model(),
+            payout_usd in 1.0f64..10_000.0,
+            costs in 0.0f64..0.01,
+            below in 0u32..1_000,
+        ) {
+            // Thresholds at or just below the exact margin are where f64 rounding could bite.
+            let candidate = FastNetMarginInputs { fee_sx, fee_azuro, payout_usd, ..inputs(odds_sx, odds_azuro, costs) };
+            let exact = compute_net_margin(candidate.to_decimal().unwrap()).unwrap();
+            let threshold = exact.net_margin - Decimal::new(i64::from(below), 12);
+            prop_assume!(threshold > -Decimal::ONE);
+            prop_assert!(passes_screen(candidate, threshold.to_f64().unwrap()));
+            let accepted = screen_and_verify(&[candidate], threshold).unwrap();
+            prop_assert_eq!(accepted.len(), 1);
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use rust_decimal::Decimal;
+use thiserror::Error;
+
+use crate::handicap::Settlement;
+use crate::net_margin::NetMarginBreakdown;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum SettlementError {
+    #[error("{0} odds must be greater than 1")]
+    InvalidOdds(&'static str),
+    #[error("{0} must be non-negative")]
+    NegativeAmount(&'static str),
+    #[error("total stake must be positive")]
+    EmptyStake,
+    #[error("expected breakdown needs a gross margin below 1 and a positive payout")]
+    InvalidExpectedMargin,
+}
+
+/// How a leg was resolved by its venue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LegOutcome {
+    Win,
+    HalfWin,
+    Push,
+    HalfLoss,
+    Loss,
+    /// Cancelled by the venue (postponement, rule mismatch); the stake is refunded.
+    Void,
+}
+
+impl LegOutcome {
+    /// Amount returned per unit staked at `decimal_odds`, stake included.
+    pub fn payout(self, decimal_odds: Decimal) -> Decimal {
+        match self {
+            LegOutcome::Win => Settlement::Win.payout(decimal_odds),
+            LegOutcome::HalfWin => Settlement::HalfWin.payout(decimal_odds),
+            LegOutcome::Push | LegOutcome::Void => Settlement::Push.payout(decimal_odds),
+            LegOutcome::HalfLoss => Settlement::HalfLoss.payout(decimal_odds),
+            LegOutcome::Loss => Settlement::Loss.payout(decimal_odds),
+        }
+    }
+
+    /// Outcomes that break the win/lose hedge the expected margin assumes.
+    fn is_partial(self) -> bool {
+        !matches!(self, LegOutcome::Win | LegOutcome::Loss)
+    }
+}
+
+impl From<Settlement> for LegOutcome {
+    fn from(settlement: Settlement) -> Self {
+        match settlement {
+            Settlement::Win => LegOutcome::Win,
+            Settlement::HalfWin => LegOutcome::HalfWin,
+            Settlement::Push => LegOutcome::Push,
+            Settlement::HalfLoss => LegOutcome::HalfLoss,
+            Settlement::Loss => LegOutcome::Loss,
+        }
+    }
+}
+
+/// One executed leg; amounts are USD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LegFill {
+    pub stake: Decimal,
+    /// Odds the expected margin was computed on.
+    pub quoted_odds: Decimal,
+    pub filled_odds: Decimal,
+    pub fees_paid: Decimal,
+    pub outcome: LegOutcome,
+}
+
+/// Field names reported by `SettlementError` for one leg.
+struct LegLabels {
+    leg: &'static str,
+    stake: &'static str,
+    fees_paid: &'static str,
+}
+
+const SX_LABELS: LegLabels = LegLabels { leg: "sx", stake: "sx stake", fees_paid: "sx fees_paid" };
+const AZURO_LABELS: LegLabels = LegLabels { leg: "azuro", stake: "azuro stake", fees_paid: "azuro fees_paid" };
+
+impl LegFill {
+    fn validate(&self, labels: &LegLabels) -> Result<(), SettlementError> {
+        if self.quoted_odds <= Decimal::ONE || self.filled_odds <= Decimal::ONE {
+            return Err(SettlementError::InvalidOdds(labels.leg));
+        }
+        if self.stake < Decimal::ZERO {
+            return Err(SettlementError::NegativeAmount(labels.stake));
+        }
+        if self.fees_paid < Decimal::ZERO {
+            return Err(SettlementError::NegativeAmount(labels.fees_paid));
+        }
+        Ok(())
+    }
+
+    fn payout(&self) -> Decimal {
+        self.stake * self.outcome.payout(self.filled_odds)
+    }
+
+    fn quoted_payout(&self) -> Decimal {
+        self.stake * self.outcome.payout(self.quoted_odds)
+    }
+}
+
+/// Both legs of a completed arbitrage and the gas actually spent, in USD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArbitrageFill {
+    pub sx: LegFill,
+    pub azuro: LegFill,
+    pub gas_paid: Decimal,
+}
+
+/// Deviation from the expected PnL split by cause, in USD; the fields sum to `deviation`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PnlAttribution {
+    /// Filled vs quoted odds, net of the slippage budgeted in the expected margin.
+    pub slippage: Decimal,
+    /// Budgeted minus paid venue fees.
+    pub fees: Decimal,
+    /// Budgeted minus paid gas.
+    pub gas: Decimal,
+    /// Void, push and half results that broke the hedge.
+    pub void: Decimal,
+    /// Stake imbalance between legs when both settled win/lose.
+    pub residual: Decimal,
+}
+
+impl PnlAttribution {
+    pub fn total(&self) -> Decimal {
+        self.slippage + self.fees + self.gas + self.void + self.residual
+    }
+}
+
+impl std::ops::Add for PnlAttribution {
+    type Output = PnlAttribution;
+
+    fn add(self, other: PnlAttribution) -> PnlAttribution {
+        PnlAttribution {
+            slippage: self.slippage + other.slippage,
+            fees: self.fees + other.fees,
+            gas: self.gas + other.gas,
+            void: self.void + other.void,
+            residual: self.residual + other.residual,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SettlementReport {
+    pub total_stake: Decimal,
+    pub payout: Decimal,
+    pub realized_pnl: Decimal,
+    /// `expected.net_margin` applied to the guaranteed payout the stakes were sized for.
+    pub expected_pnl: Decimal,
+    pub deviation: Decimal,
+    pub attribution: PnlAttribution,
+}
+
+/// Realized PnL of a settled arbitrage against the `NetMarginBreakdown` it was executed on.
+///
+/// The expected USD budgets are rescaled from `expected.payout_usd` to the payout these stakes guarantee,
+/// taken as `total_stake / (1 - gross_margin)`.
+pub fn settle_arbitrage(fill: &ArbitrageFill, expected: &NetMarginBreakdown) -> Result<SettlementReport, SettlementError> {
+    fill.sx.validate(&SX_LABELS)?;
+    fill.azuro.validate(&AZURO_LABELS)?;
+    if fill.gas_paid < Decimal::ZERO {
+        return Err(SettlementError::NegativeAmount("gas_paid"));
+    }
+    if expected.gross_margin >= Decimal::ONE || expected.payout_usd <= Decimal::ZERO {
+        return Err(SettlementError::InvalidExpectedMargin);
+    }
+    let total_stake = fill.sx.stake + fill.azuro.stake;
+    if total_stake.is_zero() {
+        return Err(SettlementError::EmptyStake);
+    }
+
+    let notional = total_stake / (Decimal::ONE - expected.gross_margin);
+    let payout = fill.sx.payout() + fill.azuro.payout();
+    let fees_paid = fill.sx.fees_paid + fill.azuro.fees_paid;
+    let realized_pnl = payout - total_stake - fees_paid - fill.gas_paid;
+    let expected_pnl = expected.net_margin * notional;
+    let scale = notional / expected.payout_usd;
+
+    let quoted_payout = fill.sx.quoted_payout() + fill.azuro.quoted_payout();
+    let outcome_effect = quoted_payout - notional;
+    let partial = fill.sx.outcome.is_partial() || fill.azuro.outcome.is_partial();
+    let attribution = PnlAttribution {
+        slippage: payout - quoted_payout + expected.slippage_total * scale,
+        fees: expected.fees_total * scale - fees_paid,
+        gas: expected.gas_total * scale - fill.gas_paid,
+        void: if partial { outcome_effect } else { Decimal::ZERO },
+        residual: if partial { Decimal::ZERO } else { outcome_effect },
+    };
+
+    Ok(SettlementReport {
+        total_stake,
+        payout,
+        realized_pnl,
+        expected_pnl,
+        deviation: realized_pnl - expected_pnl,
+        attribution,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::net_margin::{compute_net_margin, FeeModel, NetMarginInputs};
+    use std::str::FromStr;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).expect("valid decimal")
+    }
+
+    fn expected() -> NetMarginBreakdown {
+        compute_net_margin(NetMarginInputs {
+            odds_sx: dec("2.10"),
+            odds_azuro: dec("2.10"),
+            fee_sx: FeeModel::FixedUsd(dec("0.21")),
+            fee_azuro: FeeModel::FixedUsd(dec("0.21")),
+            payout_usd: dec("105"),
+            gas_usd: dec("0.105"),
+            slippage_sx: dec("0.001"),
+            slippage_azuro: dec("0.001"),
+        })
+        .expect("net margin")
+    }
+
+    fn leg(filled_odds: &str, outcome: LegOutcome) -> LegFill {
+        LegFill { stake: dec("50"), quoted_odds: dec("2.10"), filled_odds: dec(filled_odds), fees_paid: dec("0.21"), outcome }
+    }
+
+    #[test]
+    fn clean_fill_matches_expectation_up_to_budgets() {
+        let fill = ArbitrageFill { sx: leg("2.10", LegOutcome::Win), azuro: leg("2.10", LegOutcome::Loss), gas_paid: dec("0.105") };
+        let report = settle_arbitrage(&fill, &expected()).expect("settlement");
+
+        assert_eq!(report.realized_pnl, dec("4.475"));
+        // Slippage was budgeted but none happened: the deviation is exactly that budget.
+        assert_eq!(report.deviation.round_dp(10), dec("0.21"));
+        assert_eq!(report.attribution.slippage.round_dp(10), dec("0.21"));
+        assert_eq!(report.attribution.fees.round_dp(10), Decimal::ZERO);
+        assert_eq!(report.attribution.void, Decimal::ZERO);
+        assert_eq!(report.attribution.total().round_dp(10), report.deviation.round_dp(10));
+    }
+
+    #[test]
+    fn void_leg_and_worse_fill_are_attributed() {
+        let fill = ArbitrageFill { sx: leg("2.05", LegOutcome::Win), azuro: leg("2.10", LegOutcome::Void), gas_paid: dec("0.2") };
+        let report = settle_arbitrage(&fill, &expected()).expect("settlement");
+
+        assert_eq!(report.payout, dec("152.5"));
+        assert_eq!(report.realized_pnl, dec("51.88"));
+        assert_eq!(report.attribution.void.round_dp(10), dec("50"));
+        assert!(report.attribution.slippage < Decimal::ZERO);
+        assert!(report.attribution.gas < Decimal::ZERO);
+        assert_eq!(report.attribution.total().round_dp(10), report.deviation.round_dp(10));
+    }
+
+    #[test]
+    fn rejects_invalid_fills() {
+        let mut fill = ArbitrageFill { sx: leg("2.10", LegOutcome::Win), azuro: leg("1.0", LegOutcome::Loss), gas_paid: Decimal::ZERO };
+        assert_eq!(settle_arbitrage(&fill, &expected()).unwrap_err(), SettlementError::InvalidOdds("azuro"));
+        fill.azuro.filled_odds = dec("2.10");
+        fill.gas_paid = Decimal::NEGATIVE_ONE;
+        assert_eq!(settle_arbitrage(&fill, &expected()).unwrap_err(), SettlementError::NegativeAmount("gas_paid"));
+        fill.gas_paid = Decimal::ZERO;
+        fill.azuro.stake = Decimal::NEGATIVE_ONE;
+        assert_eq!(settle_arbitrage(&fill, &expected()).unwrap_err(), SettlementError::NegativeAmount("azuro stake"));
+        fill.azuro.stake = Decimal::ONE;
+        fill.sx.fees_paid = Decimal::NEGATIVE_ONE;
+        assert_eq!(settle_arbitrage(&fill, &expected()).unwrap_err(), SettlementError::NegativeAmount("sx fees_paid"));
+    }
+}
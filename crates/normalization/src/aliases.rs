@@ -0,0 +1,451 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::fold::fold_to_ascii;
+use crate::market_uid::MarketIdentifier;
+
+/// Tokens dropped before matching ("Chelsea FC" == "Chelsea").
+const STOPWORDS: [&str; 6] = ["fc", "cf", "afc", "sc", "club", "the"];
+/// Event separators recognised when splitting an affiche into teams.
+const EVENT_SEPARATORS: [&str; 5] = [" vs. ", " vs ", " v ", AWAY_AT_HOME, " - "];
+/// US `away @ home` separator.
+const AWAY_AT_HOME: &str = " @ ";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AliasKind {
+    League,
+    Team,
+}
+
+impl AliasKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AliasKind::League => "league",
+            AliasKind::Team => "team",
+        }
+    }
+}
+
+impl fmt::Display for AliasKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AliasError {
+    #[error("failed to read alias file `{path}`: {source}")]
+    Io { path: String, source: std::io::Error },
+    #[error("invalid alias file: {0}")]
+    Parse(String),
+    #[error("alias `{alias}` ({sport}/{kind}) maps to both `{existing}` and `{canonical}`")]
+    Conflict { sport: String, kind: AliasKind, alias: String, existing: String, canonical: String },
+    #[error("alias dictionary version mismatch: {0} vs {1}")]
+    VersionMismatch(u32, u32),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AliasFile {
+    version: u32,
+    #[serde(default)]
+    sports: BTreeMap<String, SportAliases>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SportAliases {
+    #[serde(default)]
+    leagues: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    teams: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct AliasRow {
+    version: u32,
+    sport: String,
+    kind: AliasKind,
+    canonical: String,
+    alias: String,
+}
+
+#[derive(Clone, Debug)]
+struct AliasEntry {
+    canonical: String,
+    keys: Vec<String>,
+}
+
+/// Versioned league/team alias tables, keyed by sport.
+#[derive(Clone, Debug, Default)]
+pub struct AliasDictionary {
+    version: u32,
+    entries: HashMap<(String, AliasKind), Vec<AliasEntry>>,
+    exact: HashMap<(String, AliasKind, String), usize>,
+}
+
+impl AliasDictionary {
+    pub fn new(version: u32) -> Self {
+        Self { version, ..Self::default() }
+    }
+
+    /// YAML layout: `version` + `sports.<sport>.{leagues,teams}.<canonical>: [aliases]`.
+    pub fn from_yaml_str(input: &str) -> Result<Self, AliasError> {
+        let file: AliasFile = serde_yaml::from_str(input).map_err(|err| AliasError::Parse(err.to_string()))?;
+        let mut dictionary = Self::new(file.version);
+        for (sport, tables) in file.sports {
+            for (kind, table) in [(AliasKind::League, tables.leagues), (AliasKind::Team, tables.teams)] {
+                for (canonical, aliases) in table {
+                    dictionary.insert(&sport, kind, &canonical, aliases.iter().map(String::as_str))?;
+                }
+            }
+        }
+        Ok(dictionary)
+    }
+
+    /// CSV layout: `version,sport,kind,canonical,alias`, one alias per row; every row must share the version.
+    pub fn from_csv_reader(reader: impl Read) -> Result<Self, AliasError> {
+        let mut dictionary: Option<Self> = None;
+        for row in csv::Reader::from_reader(reader).deserialize::<AliasRow>() {
+            let row = row.map_err(|err| AliasError::Parse(err.to_string()))?;
+            let dictionary = dictionary.get_or_insert_with(|| Self::new(row.version));
+            if dictionary.version != row.version {
+                return Err(AliasError::VersionMismatch(dictionary.version, row.version));
+            }
+            dictionary.insert(&row.sport, row.kind, &row.canonical, [row.alias.as_str()])?;
+        }
+        dictionary.ok_or_else(|| AliasError::Parse("empty alias csv".into()))
+    }
+
+    /// Loads a `.yml`/`.yaml` or `.csv` alias file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, AliasError> {
+        let path = path.as_ref();
+        let io_error = |source| AliasError::Io { path: path.display().to_string(), source };
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("csv") => Self::from_csv_reader(fs::File::open(path).map_err(io_error)?),
+            _ => Self::from_yaml_str(&fs::read_to_string(path).map_err(io_error)?),
+        }
+    }
+
+    /// Folds another file of the same version into this dictionary.
+    pub fn merge(&mut self, other: AliasDictionary) -> Result<(), AliasError> {
+        if self.version != other.version {
+            return Err(AliasError::VersionMismatch(self.version, other.version));
+        }
+        for ((sport, kind), entries) in other.entries {
+            for entry in entries {
+                self.insert(&sport, kind, &entry.canonical, entry.keys.iter().map(String::as_str))?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn insert<'a>(
+        &mut self,
+        sport: &str,
+        kind: AliasKind,
+        canonical: &'a str,
+        aliases: impl IntoIterator<Item = &'a str>,
+    ) -> Result<(), AliasError> {
+        let sport = match_key(sport);
+        let canonical = canonical.trim();
+        let group = self.entries.entry((sport.clone(), kind)).or_default();
+        let index = match group.iter().position(|entry| entry.canonical == canonical) {
+            Some(index) => index,
+            None => {
+                group.push(AliasEntry { canonical: canonical.to_string(), keys: Vec::new() });
+                group.len() - 1
+            }
+        };
+        for alias in std::iter::once(canonical).chain(aliases) {
+            let key = match_key(alias);
+            if key.is_empty() {
+                continue;
+            }
+            match self.exact.get(&(sport.clone(), kind, key.clone())) {
+                Some(&existing) if existing != index => {
+                    return Err(AliasError::Conflict {
+                        sport,
+                        kind,
+                        alias: alias.to_string(),
+                        existing: group[existing].canonical.clone(),
+                        canonical: canonical.to_string(),
+                    });
+                }
+                Some(_) => {}
+                None => {
+                    self.exact.insert((sport.clone(), kind, key.clone()), index);
+                    group[index].keys.push(key);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Fuzzy matching thresholds on a 0..=1 score.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+pub struct FuzzyConfig {
+    /// Fuzzy matches at or above this score are merged automatically.
+    pub accept: f64,
+    /// Matches between `review` and `accept` are queued for a human instead of merged.
+    pub review: f64,
+    /// Two candidates closer than this are ambiguous and always queued.
+    pub ambiguity_margin: f64,
+}
+
+impl Default for FuzzyConfig {
+    fn default() -> Self {
+        Self { accept: 0.95, review: 0.80, ambiguity_margin: 0.02 }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMethod {
+    Exact,
+    Fuzzy,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AliasMatch {
+    pub canonical: String,
+    pub method: MatchMethod,
+    pub score: f64,
+}
+
+/// Low-confidence or ambiguous match left unmerged until reviewed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReviewItem {
+    pub dictionary_version: u32,
+    pub sport: String,
+    pub kind: AliasKind,
+    pub raw: String,
+    pub candidate: String,
+    pub score: f64,
+    pub ambiguous: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Resolution {
+    Resolved(AliasMatch),
+    Review(ReviewItem),
+    Unknown,
+}
+
+/// Pending review items, de-duplicated on (sport, kind, raw name).
+#[derive(Debug, Default)]
+pub struct ReviewQueue {
+    items: Mutex<Vec<ReviewItem>>,
+}
+
+impl ReviewQueue {
+    pub fn push(&self, item: ReviewItem) {
+        let mut items = self.lock();
+        let known = items.iter().any(|existing| {
+            existing.sport == item.sport && existing.kind == item.kind && existing.raw == item.raw
+        });
+        if !known {
+            items.push(item);
+        }
+    }
+
+    pub fn pending(&self) -> Vec<ReviewItem> {
+        self.lock().clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    pub fn drain(&self) -> Vec<ReviewItem> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<ReviewItem>> {
+        self.items.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Resolves raw league/team names against an `AliasDictionary`: exact alias first, fuzzy fallback second.
+#[derive(Debug)]
+pub struct AliasResolver {
+    dictionary: AliasDictionary,
+    config: FuzzyConfig,
+    review: ReviewQueue,
+}
+
+impl AliasResolver {
+    pub fn new(dictionary: AliasDictionary) -> Self {
+        Self { dictionary, config: FuzzyConfig::default(), review: ReviewQueue::default() }
+    }
+
+    pub fn with_config(mut self, config: FuzzyConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn dictionary(&self) -> &AliasDictionary {
+        &self.dictionary
+    }
+
+    pub fn review_queue(&self) -> &ReviewQueue {
+        &self.review
+    }
+
+    /// Review outcomes are also pushed to the review queue.
+    pub fn resolve(&self, sport: &str, kind: AliasKind, raw: &str) -> Resolution {
+        let sport_key = match_key(sport);
+        let key = match_key(raw);
+        let Some(entries) = self.dictionary.entries.get(&(sport_key.clone(), kind)) else {
+            return Resolution::Unknown;
+        };
+        if let Some(&index) = self.dictionary.exact.get(&(sport_key.clone(), kind, key.clone())) {
+            let canonical = entries[index].canonical.clone();
+            return Resolution::Resolved(AliasMatch { canonical, method: MatchMethod::Exact, score: 1.0 });
+        }
+
+        let mut scored: Vec<(f64, &AliasEntry)> = entries
+            .iter()
+            .map(|entry| (entry.keys.iter().map(|alias| similarity(&key, alias)).fold(0.0, f64::max), entry))
+            .collect();
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+        let Some(&(score, best)) = scored.first() else {
+            return Resolution::Unknown;
+        };
+        let ambiguous = scored.get(1).is_some_and(|&(runner_up, _)| score - runner_up < self.config.ambiguity_margin);
+        if score >= self.config.accept && !ambiguous {
+            return Resolution::Resolved(AliasMatch { canonical: best.canonical.clone(), method: MatchMethod::Fuzzy, score });
+        }
+        if score < self.config.review {
+            return Resolution::Unknown;
+        }
+        let item = ReviewItem {
+            dictionary_version: self.dictionary.version,
+            sport: sport_key,
+            kind,
+            raw: raw.trim().to_string(),
+            candidate: best.canonical.clone(),
+            score,
+            ambiguous,
+        };
+        self.review.push(item.clone());
+        Resolution::Review(item)
+    }
+
+    /// Canonical name when resolved, the raw name untouched otherwise.
+    pub fn canonical_name(&self, sport: &str, kind: AliasKind, raw: &str) -> String {
+        match self.resolve(sport, kind, raw) {
+            Resolution::Resolved(found) => found.canonical,
+            Resolution::Review(_) | Resolution::Unknown => raw.to_string(),
+        }
+    }
+
+    /// Rewrites league and event teams to their canonical names; the event is re-joined home first with ` vs `.
+    pub fn apply(&self, identifier: &MarketIdentifier) -> MarketIdentifier {
+        let sport = identifier.sport.as_str();
+        let league = self.canonical_name(sport, AliasKind::League, &identifier.league);
+        let event = match split_event(&identifier.event) {
+            Some((home, away)) => format!(
+                "{} vs {}",
+                self.canonical_name(sport, AliasKind::Team, home),
+                self.canonical_name(sport, AliasKind::Team, away)
+            ),
+            None => self.canonical_name(sport, AliasKind::Team, &identifier.event),
+        };
+        MarketIdentifier { league, event, ..identifier.clone() }
+    }
+}
+
+/// `(home, away)`; US notation `A @ B` lists the away side first and is flipped.
+fn split_event(event: &str) -> Option<(&str, &str)> {
+    let lowered = event.to_ascii_lowercase();
+    EVENT_SEPARATORS.iter().find_map(|separator| {
+        let at = lowered.find(separator)?;
+        let (left, right) = (event[..at].trim(), event[at + separator.len()..].trim());
+        if left.is_empty() || right.is_empty() {
+            return None;
+        }
+        Some(if *separator == AWAY_AT_HOME { (right, left) } else { (left, right) })
+    })
+}
+
+/// Folded alphanumeric tokens without club stopwords, joined by a space.
+fn match_key(input: &str) -> String {
+    let lowered: String = fold_to_ascii(input)
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    let tokens: Vec<&str> = lowered.split_whitespace().collect();
+    let kept: Vec<&str> = tokens.iter().copied().filter(|token| !STOPWORDS.contains(token)).collect();
+    if kept.is_empty() { tokens.join(" ") } else { kept.join(" ") }
+}
+
+/// Best of token-set overlap (Dice) and Jaro-Winkler on the raw and token-sorted strings.
+fn similarity(a: &str, b: &str) -> f64 {
+    let in_order = strsim::jaro_winkler(a, b);
+    let mut left: Vec<&str> = a.split_whitespace().collect();
+    let mut right: Vec<&str> = b.split_whitespace().collect();
+    left.sort_unstable();
+    left.dedup();
+    right.sort_unstable();
+    right.dedup();
+    if left.is_empty() || right.is_empty() {
+        return 0.0;
+    }
+    let shared = left.iter().filter(|token| right.binary_search(token).is_ok()).count();
+    let dice = 2.0 * shared as f64 / (left.len() + right.len()) as f64;
+    dice.max(in_order).max(strsim::jaro_winkler(&left.join(" "), &right.join(" ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_key_drops_club_suffixes_and_punctuation() {
+        assert_eq!(match_key("  Manchester Utd FC "), "manchester utd");
+        assert_eq!(match_key("Paris Saint-Germain"), "paris saint germain");
+        assert_eq!(match_key("Bayern München"), "bayern munchen");
+        assert_eq!(match_key("Chelsea FC"), "chelsea");
+        assert_eq!(match_key("FC"), "fc");
+    }
+
+    #[test]
+    fn similarity_is_order_insensitive() {
+        assert!((similarity("united manchester", "manchester united") - 1.0).abs() < f64::EPSILON);
+        assert!(similarity("arsenal", "chelsea") < 0.8);
+        assert!(similarity("manchester", "manchester city") < FuzzyConfig::default().accept);
+    }
+
+    #[test]
+    fn split_event_handles_common_separators() {
+        assert_eq!(split_event("Lakers @ Warriors"), Some(("Warriors", "Lakers")));
+        assert_eq!(split_event("Arsenal VS Chelsea"), Some(("Arsenal", "Chelsea")));
+        assert_eq!(split_event("Djokovic"), None);
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+
+use thiserror::Error;
+
+use crate::market_uid::{MarketIdentifier, MarketUid, MarketUidError, UidVersion};
+use crate::migration::{read_seed, MigrationError};
+
+#[derive(Debug, Error)]
+pub enum CatalogError {
+    #[error(transparent)]
+    Uid(#[from] MarketUidError),
+    #[error(transparent)]
+    Seed(#[from] MigrationError),
+    #[error("seed row {row}: recorded `{recorded}` but the identifier yields `{computed}`")]
+    SeedMismatch { row: usize, recorded: MarketUid, computed: MarketUid },
+    /// Two distinct fingerprints share the truncated hash; the first one stays in the catalog.
+    #[error("hash collision on `{uid}`: `{existing}` vs `{incoming}`")]
+    Collision { uid: MarketUid, existing: String, incoming: String },
+}
+
+/// What a `MarketUid` stands for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CatalogEntry {
+    pub uid: MarketUid,
+    pub identifier: MarketIdentifier,
+    /// Canonical fingerprint that was hashed into `uid`.
+    pub fingerprint: String,
+}
+
+impl CatalogEntry {
+    /// Human-readable market name for logs and dashboards.
+    pub fn label(&self) -> String {
+        let identifier = &self.identifier;
+        format!(
+            "{} · {} · {} · {}/{} · {}",
+            identifier.operator.trim(),
+            identifier.league.trim(),
+            identifier.event.trim(),
+            identifier.market_type.trim(),
+            identifier.outcome.trim(),
+            identifier.kickoff_minute().format("%Y-%m-%d %H:%MZ"),
+        )
+    }
+}
+
+impl fmt::Display for CatalogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.label())
+    }
+}
+
+/// Reverse index `MarketUid → MarketIdentifier`, filled from the seed CSV or from live feeds.
+#[derive(Clone, Debug, Default)]
+pub struct MarketCatalog {
+    entries: HashMap<MarketUid, CatalogEntry>,
+}
+
+impl MarketCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Loads `data/market_uid_seed.csv`, checking every recorded UID against its identifier.
+    pub fn from_seed(reader: impl Read) -> Result<Self, CatalogError> {
+        let mut catalog = Self::new();
+        for (index, entry) in read_seed(reader)?.into_iter().enumerate() {
+            let uid = catalog.insert(entry.identifier, entry.market_uid.version())?;
+            if uid != entry.market_uid {
+                return Err(CatalogError::SeedMismatch { row: index + 1, recorded: entry.market_uid, computed: uid });
+            }
+        }
+        Ok(catalog)
+    }
+
+    /// Indexes one identifier; re-inserting the same market is a no-op.
+    pub fn insert(&mut self, identifier: MarketIdentifier, version: UidVersion) -> Result<MarketUid, CatalogError> {
+        let uid = MarketUid::from_identifier_versioned(&identifier, version)?;
+        let fingerprint = identifier.canonical_fingerprint_versioned(version)?;
+        self.insert_entry(CatalogEntry { uid: uid.clone(), identifier, fingerprint })?;
+        Ok(uid)
+    }
+
+    fn insert_entry(&mut self, entry: CatalogEntry) -> Result<(), CatalogError> {
+        if let Some(existing) = self.entries.get(&entry.uid) {
+            if existing.fingerprint == entry.fingerprint {
+                return Ok(());
+            }
+            return Err(CatalogError::Collision {
+                uid: entry.uid,
+                existing: existing.fingerprint.clone(),
+                incoming: entry.fingerprint,
+            });
+        }
+        self.entries.insert(entry.uid.clone(), entry);
+        Ok(())
+    }
+
+    pub fn get(&self, uid: &MarketUid) -> Option<&CatalogEntry> {
+        self.entries.get(uid)
+    }
+
+    pub fn identifier(&self, uid: &MarketUid) -> Option<&MarketIdentifier> {
+        self.get(uid).map(|entry| &entry.identifier)
+    }
+
+    /// Label of a known market, or the UID itself so log lines never go blank.
+    pub fn describe(&self, uid: &MarketUid) -> String {
+        self.get(uid).map_or_else(|| uid.to_string(), CatalogEntry::label)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &CatalogEntry> {
+        self.entries.values()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn identifier(outcome: &str) -> MarketIdentifier {
+        MarketIdentifier {
+            operator: "sx".into(),
+            sport: "Soccer".into(),
+            league: "Premier League".into(),
+            event: "Arsenal vs Chelsea".into(),
+            market_type: "moneyline".into(),
+            outcome: outcome.into(),
+            event_timestamp: chrono::Utc.with_ymd_and_hms(2024, 8, 10, 16, 30, 0).unwrap(),
+            variant: Some("pre".into()),
+            ladder: None,
+        }
+    }
+
+    #[test]
+    fn resolves_inserted_markets_and_ignores_reinsertion() {
+        let mut catalog = MarketCatalog::new();
+        let uid = catalog.insert(identifier("home"), UidVersion::V1).unwrap();
+        assert_eq!(uid.as_str(), "muid-v1-b05bf41737061f9a2d1595d7");
+        assert_eq!(catalog.insert(identifier("home"), UidVersion::V1).unwrap(), uid);
+        assert_eq!(catalog.len(), 1);
+        assert_eq!(catalog.describe(&uid), "sx · Premier League · Arsenal vs Chelsea · moneyline/home · 2024-08-10 16:30Z");
+
+        let unknown = MarketUid::parse("muid-v1-000000000000000000000001").unwrap();
+        assert_eq!(catalog.describe(&unknown), unknown.as_str());
+    }
+
+    #[test]
+    fn truncated_hash_collisions_are_rejected() {
+        let mut catalog = MarketCatalog::new();
+        let uid = catalog.insert(identifier("home"), UidVersion::V1).unwrap();
+        let forged = CatalogEntry { uid: uid.clone(), identifier: identifier("away"), fingerprint: "muid|v1|forged".into() };
+
+        let err = catalog.insert_entry(forged).unwrap_err();
+        assert!(matches!(err, CatalogError::Collision { uid: collided, .. } if collided == uid));
+        assert_eq!(catalog.identifier(&uid).unwrap().outcome, "home");
+    }
+}
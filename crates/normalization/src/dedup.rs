@@ -1,6 +1,11 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
 
 use crate::market_uid::MarketUid;
+use crate::migration::UidMigration;
 
 /// Key composed of a market UID and normalized side label.
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -20,21 +25,146 @@ impl DedupKey {
 pub struct MarketRecord<T> {
     pub key: DedupKey,
     pub source: String,
+    /// When the quote was observed at the source; used by `LatestTimestamp`.
+    pub observed_at: Option<DateTime<Utc>>,
+    /// Decimal odds of the quote; used by `BestOdds`.
+    pub odds: Option<Decimal>,
     pub payload: T,
 }
 
 impl<T> MarketRecord<T> {
     pub fn new(uid: MarketUid, side: impl AsRef<str>, source: impl Into<String>, payload: T) -> Self {
         let key = DedupKey::new(uid, side);
-        Self { key, source: source.into(), payload }
+        Self { key, source: source.into(), observed_at: None, odds: None, payload }
+    }
+
+    pub fn with_observed_at(mut self, observed_at: DateTime<Utc>) -> Self {
+        self.observed_at = Some(observed_at);
+        self
+    }
+
+    pub fn with_odds(mut self, odds: Decimal) -> Self {
+        self.odds = Some(odds);
+        self
+    }
+}
+
+/// Why a record lost against another one with the same key.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DropReason {
+    /// Another record held the key first and the policy had no reason to replace it.
+    AlreadySeen,
+    /// Observed before the retained record, or without a timestamp.
+    Stale,
+    /// Lower odds than the retained record, or no odds.
+    WorseOdds,
+    /// Source ranked below the retained record's source.
+    LowerPriority,
+}
+
+impl fmt::Display for DropReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            DropReason::AlreadySeen => "already_seen",
+            DropReason::Stale => "stale",
+            DropReason::WorseOdds => "worse_odds",
+            DropReason::LowerPriority => "lower_priority",
+        };
+        f.write_str(label)
+    }
+}
+
+/// Which of two same-key records survives, and why the other one is dropped.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Choice {
+    KeepExisting(DropReason),
+    TakeIncoming(DropReason),
+}
+
+/// Retention policy applied when a record arrives for a key that is already held.
+pub trait DedupPolicy<T> {
+    fn choose(&self, existing: &MarketRecord<T>, incoming: &MarketRecord<T>) -> Choice;
+}
+
+/// The first record per key wins (historical behaviour).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FirstSeen;
+
+impl<T> DedupPolicy<T> for FirstSeen {
+    fn choose(&self, _existing: &MarketRecord<T>, _incoming: &MarketRecord<T>) -> Choice {
+        Choice::KeepExisting(DropReason::AlreadySeen)
+    }
+}
+
+/// The most recently observed quote wins; records without `observed_at` lose to timestamped ones.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LatestTimestamp;
+
+impl<T> DedupPolicy<T> for LatestTimestamp {
+    fn choose(&self, existing: &MarketRecord<T>, incoming: &MarketRecord<T>) -> Choice {
+        prefer_greater(existing.observed_at, incoming.observed_at, DropReason::Stale)
+    }
+}
+
+/// The highest decimal odds win; records without `odds` lose to priced ones.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BestOdds;
+
+impl<T> DedupPolicy<T> for BestOdds {
+    fn choose(&self, existing: &MarketRecord<T>, incoming: &MarketRecord<T>) -> Choice {
+        prefer_greater(existing.odds, incoming.odds, DropReason::WorseOdds)
+    }
+}
+
+/// Sources listed first win; unlisted sources rank below every listed one.
+#[derive(Clone, Debug, Default)]
+pub struct SourcePriority {
+    ranking: Vec<String>,
+}
+
+impl SourcePriority {
+    pub fn new(ranking: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        Self { ranking: ranking.into_iter().map(|source| source.as_ref().trim().to_ascii_lowercase()).collect() }
+    }
+
+    fn rank(&self, source: &str) -> Option<usize> {
+        let source = source.trim().to_ascii_lowercase();
+        self.ranking.iter().position(|ranked| *ranked == source)
     }
 }
 
+impl<T> DedupPolicy<T> for SourcePriority {
+    fn choose(&self, existing: &MarketRecord<T>, incoming: &MarketRecord<T>) -> Choice {
+        // Lower index is better, so compare the negated ranks.
+        let score = |source: &str| self.rank(source).map(std::cmp::Reverse);
+        prefer_greater(score(&existing.source), score(&incoming.source), DropReason::LowerPriority)
+    }
+}
+
+/// Incoming replaces existing only when strictly greater; ties keep the first-seen record.
+fn prefer_greater<V: Ord>(existing: Option<V>, incoming: Option<V>, reason: DropReason) -> Choice {
+    match (existing, incoming) {
+        (None, None) => Choice::KeepExisting(DropReason::AlreadySeen),
+        (Some(existing), Some(incoming)) if existing == incoming => Choice::KeepExisting(DropReason::AlreadySeen),
+        (existing, incoming) if incoming > existing => Choice::TakeIncoming(reason),
+        _ => Choice::KeepExisting(reason),
+    }
+}
+
+/// A record removed by deduplication, with the reason it lost.
+#[derive(Clone, Debug)]
+pub struct Dropped<T> {
+    pub record: MarketRecord<T>,
+    pub reason: DropReason,
+}
+
 /// Outcome of the deduplication pass.
 #[derive(Clone, Debug, Default)]
 pub struct DedupResult<T> {
     pub retained: Vec<MarketRecord<T>>,
     pub duplicates: Vec<MarketRecord<T>>,
+    /// `drop_reasons[i]` explains why `duplicates[i]` was dropped.
+    pub drop_reasons: Vec<DropReason>,
 }
 
 impl<T> DedupResult<T> {
@@ -47,23 +177,67 @@ impl<T> DedupResult<T> {
         let dup = self.duplicates.len() as f64;
         if kept + dup == 0.0 { 0.0 } else { dup / (kept + dup) }
     }
+
+    pub fn dropped_for(&self, reason: DropReason) -> usize {
+        self.drop_reasons.iter().filter(|dropped| **dropped == reason).count()
+    }
+
+    /// Each dropped record paired with its reason, in drop order.
+    pub fn dropped(&self) -> impl Iterator<Item = (&MarketRecord<T>, DropReason)> {
+        self.duplicates.iter().zip(self.drop_reasons.iter().copied())
+    }
 }
 
 /// Deduplicate records by Market UID + side while preserving first-seen priority.
 pub fn deduplicate<T>(records: impl IntoIterator<Item = MarketRecord<T>>) -> DedupResult<T> {
-    let mut seen = HashSet::new();
-    let mut retained = Vec::new();
+    deduplicate_with(records, &FirstSeen)
+}
+
+/// Deduplicate records by Market UID + side, letting `policy` pick the survivor of each collision.
+/// Retained records keep the position of the first record seen for their key.
+pub fn deduplicate_with<T>(records: impl IntoIterator<Item = MarketRecord<T>>, policy: &impl DedupPolicy<T>) -> DedupResult<T> {
+    deduplicate_by(records, policy, |key| key.clone())
+}
+
+/// Same as `deduplicate_with`, but old and new UIDs of one market collide while `migration` is active at `at`.
+pub fn deduplicate_with_migration<T>(
+    records: impl IntoIterator<Item = MarketRecord<T>>,
+    policy: &impl DedupPolicy<T>,
+    migration: &UidMigration,
+    at: DateTime<Utc>,
+) -> DedupResult<T> {
+    deduplicate_by(records, policy, |key| DedupKey { uid: migration.translate(&key.uid, at).clone(), side: key.side.clone() })
+}
+
+fn deduplicate_by<T>(
+    records: impl IntoIterator<Item = MarketRecord<T>>,
+    policy: &impl DedupPolicy<T>,
+    identity: impl Fn(&DedupKey) -> DedupKey,
+) -> DedupResult<T> {
+    let mut slots = HashMap::new();
+    let mut retained: Vec<MarketRecord<T>> = Vec::new();
     let mut duplicates = Vec::new();
+    let mut drop_reasons = Vec::new();
 
     for record in records {
-        if seen.insert(record.key.clone()) {
+        let Some(&slot) = slots.get(&identity(&record.key)) else {
+            slots.insert(identity(&record.key), retained.len());
             retained.push(record);
-        } else {
-            duplicates.push(record);
+            continue;
+        };
+        match policy.choose(&retained[slot], &record) {
+            Choice::KeepExisting(reason) => {
+                duplicates.push(record);
+                drop_reasons.push(reason);
+            }
+            Choice::TakeIncoming(reason) => {
+                duplicates.push(std::mem::replace(&mut retained[slot], record));
+                drop_reasons.push(reason);
+            }
         }
     }
 
-    DedupResult { retained, duplicates }
+    DedupResult { retained, duplicates, drop_reasons }
 }
 
 fn normalize_side(value: &str) -> String {
@@ -76,3 +250,51 @@ fn normalize_side(value: &str) -> String {
         .join("_")
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn record(source: &str, minute: u32, odds: &str) -> MarketRecord<&'static str> {
+        let uid = MarketUid::parse("muid-v1-b05bf41737061f9a2d1595d7").unwrap();
+        MarketRecord::new(uid, "home", source, "quote")
+            .with_observed_at(Utc.with_ymd_and_hms(2024, 8, 10, 16, minute, 0).unwrap())
+            .with_odds(odds.parse().unwrap())
+    }
+
+    fn quotes() -> Vec<MarketRecord<&'static str>> {
+        vec![record("azuro", 0, "2.10"), record("sx", 5, "2.05"), record("SX", 1, "2.20")]
+    }
+
+    #[test]
+    fn policies_pick_their_survivor_and_record_why() {
+        let first = deduplicate(quotes());
+        assert_eq!(first.retained[0].source, "azuro");
+        assert_eq!(first.dropped_for(DropReason::AlreadySeen), 2);
+
+        let latest = deduplicate_with(quotes(), &LatestTimestamp);
+        assert_eq!(latest.retained[0].source, "sx");
+        assert_eq!(latest.dropped_for(DropReason::Stale), 2);
+
+        let best = deduplicate_with(quotes(), &BestOdds);
+        assert_eq!(best.retained[0].odds, Some("2.20".parse().unwrap()));
+        assert_eq!(best.duplicates.iter().map(|dropped| dropped.source.as_str()).collect::<Vec<_>>(), ["sx", "azuro"]);
+        assert!(best.dropped().all(|(_, reason)| reason == DropReason::WorseOdds));
+
+        let ranked = deduplicate_with(quotes(), &SourcePriority::new(["sx", "azuro"]));
+        assert_eq!(ranked.retained[0].source, "sx");
+        assert_eq!(ranked.drop_reasons, [DropReason::LowerPriority, DropReason::AlreadySeen]);
+    }
+
+    #[test]
+    fn missing_data_loses_and_ties_keep_the_first_record() {
+        let uid = MarketUid::parse("muid-v1-b05bf41737061f9a2d1595d7").unwrap();
+        let bare = MarketRecord::new(uid, "home", "polymarket", "bare");
+        let result = deduplicate_with(vec![bare.clone(), record("sx", 0, "2.0")], &BestOdds);
+        assert_eq!(result.retained[0].source, "sx");
+        assert_eq!(result.drop_reasons[0], DropReason::WorseOdds);
+
+        let unranked = deduplicate_with(vec![bare.clone(), bare], &SourcePriority::new(["sx"]));
+        assert_eq!(unranked.drop_reasons[0], DropReason::AlreadySeen);
+    }
+}
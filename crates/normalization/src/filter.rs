@@ -0,0 +1,342 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::SystemTime;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::dedup::MarketRecord;
+use crate::market_uid::{AsciiStrategy, FingerprintStrategy, MarketIdentifier};
+use crate::taxonomy::{MarketFamily, MarketSpec};
+
+/// `families` key applying to sports without their own entry.
+const WILDCARD: &str = "*";
+const LIVE_VARIANTS: [&str; 3] = ["live", "in_play", "inplay"];
+
+#[derive(Debug, Error)]
+pub enum FilterError {
+    #[error("cannot read {path}: {message}")]
+    Io { path: String, message: String },
+    #[error("invalid risk config: {0}")]
+    Parse(String),
+    #[error("invalid market filter: {0}")]
+    Invalid(String),
+}
+
+/// The `markets` section of `config/risk.yml`; keys unrelated to filtering (`odds_slippage`) are ignored.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarketFilterConfig {
+    /// Whitelist; empty admits every league not excluded.
+    #[serde(default)]
+    pub included_leagues: Vec<String>,
+    /// `League` or `OPERATOR:League`.
+    #[serde(default)]
+    pub excluded_leagues: Vec<String>,
+    /// Operator market types (`player-props`).
+    #[serde(default)]
+    pub excluded_markets: Vec<String>,
+    /// Allowed families per sport; `"*"` covers unlisted sports, no entry means unrestricted.
+    #[serde(default)]
+    pub families: BTreeMap<String, Vec<MarketFamily>>,
+    /// Markets closer than this to kick-off, or already started, are rejected.
+    #[serde(default)]
+    pub pre_match_cutoff_secs: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct RiskFile {
+    markets: MarketFilterConfig,
+}
+
+/// Why a record was kept out of dedup and pairing.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum RejectReason {
+    LeagueExcluded { league: String },
+    LeagueNotIncluded { league: String },
+    MarketExcluded { market_type: String },
+    UnknownMarket { market_type: String },
+    FamilyNotAllowed { sport: String, family: MarketFamily },
+    LiveMarket,
+    PastCutoff { kickoff: DateTime<Utc> },
+}
+
+impl fmt::Display for RejectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RejectReason::LeagueExcluded { league } => write!(f, "league `{league}` is excluded"),
+            RejectReason::LeagueNotIncluded { league } => write!(f, "league `{league}` is not whitelisted"),
+            RejectReason::MarketExcluded { market_type } => write!(f, "market `{market_type}` is excluded"),
+            RejectReason::UnknownMarket { market_type } => write!(f, "market `{market_type}` is outside the taxonomy"),
+            RejectReason::FamilyNotAllowed { sport, family } => write!(f, "{family} is not allowed for {sport}"),
+            RejectReason::LiveMarket => f.write_str("live market"),
+            RejectReason::PastCutoff { kickoff } => write!(f, "too close to kick-off {kickoff}"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FilterDecision {
+    Admitted,
+    Rejected(RejectReason),
+}
+
+#[derive(Clone, Debug)]
+pub struct Rejected<T> {
+    pub identifier: MarketIdentifier,
+    pub record: MarketRecord<T>,
+    pub reason: RejectReason,
+}
+
+/// Records split by `MarketFilter::apply`; `admitted` feeds dedup and pairing unchanged.
+#[derive(Clone, Debug)]
+pub struct FilterResult<T> {
+    pub admitted: Vec<(MarketIdentifier, MarketRecord<T>)>,
+    pub rejected: Vec<Rejected<T>>,
+}
+
+/// League / market perimeter from the risk config, with the pre-match cutoff.
+#[derive(Clone, Debug, Default)]
+pub struct MarketFilter {
+    included_leagues: HashSet<String>,
+    excluded_leagues: HashSet<(Option<String>, String)>,
+    excluded_markets: HashSet<String>,
+    families: BTreeMap<String, HashSet<MarketFamily>>,
+    cutoff: Option<Duration>,
+}
+
+impl MarketFilter {
+    pub fn new(config: &MarketFilterConfig) -> Result<Self, FilterError> {
+        let mut problems = Vec::new();
+        let mut families = BTreeMap::new();
+        for (sport, allowed) in &config.families {
+            if allowed.is_empty() {
+                problems.push(format!("families.{sport} is empty"));
+            }
+            families.insert(selector(sport), allowed.iter().copied().collect());
+        }
+        let excluded_leagues = config
+            .excluded_leagues
+            .iter()
+            .map(|entry| match entry.split_once(':') {
+                Some((operator, league)) => (Some(canonical(operator)), canonical(league)),
+                None => (None, canonical(entry)),
+            })
+            .collect::<HashSet<_>>();
+        let lists = [("included_leagues", &config.included_leagues), ("excluded_leagues", &config.excluded_leagues), ("excluded_markets", &config.excluded_markets)];
+        for (name, list) in lists {
+            if list.iter().any(|entry| canonical(entry).is_empty()) {
+                problems.push(format!("{name} contains an empty entry"));
+            }
+        }
+        let cutoff = config.pre_match_cutoff_secs.map(|secs| i64::try_from(secs).map(Duration::seconds));
+        let cutoff = match cutoff.transpose() {
+            Ok(cutoff) => cutoff,
+            Err(_) => {
+                problems.push("pre_match_cutoff_secs is too large".to_string());
+                None
+            }
+        };
+        if !problems.is_empty() {
+            return Err(FilterError::Invalid(problems.join("; ")));
+        }
+        Ok(Self {
+            included_leagues: config.included_leagues.iter().map(|league| canonical(league)).collect(),
+            excluded_leagues,
+            excluded_markets: config.excluded_markets.iter().map(|market| canonical(market)).collect(),
+            families,
+            cutoff,
+        })
+    }
+
+    /// Reads the `markets` section of a whole `risk.yml`.
+    pub fn from_risk_yaml(input: &str) -> Result<Self, FilterError> {
+        let file: RiskFile = serde_yaml::from_str(input).map_err(|err| FilterError::Parse(err.to_string()))?;
+        Self::new(&file.markets)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, FilterError> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|err| FilterError::Io { path: path.display().to_string(), message: err.to_string() })?;
+        Self::from_risk_yaml(&content)
+    }
+
+    /// Checks run in order: leagues, market type, family, live flag, cutoff against `now`.
+    pub fn evaluate(&self, identifier: &MarketIdentifier, now: DateTime<Utc>) -> FilterDecision {
+        match self.reject_reason(identifier, now) {
+            Some(reason) => FilterDecision::Rejected(reason),
+            None => FilterDecision::Admitted,
+        }
+    }
+
+    pub fn apply<T>(&self, records: impl IntoIterator<Item = (MarketIdentifier, MarketRecord<T>)>, now: DateTime<Utc>) -> FilterResult<T> {
+        let mut result = FilterResult { admitted: Vec::new(), rejected: Vec::new() };
+        for (identifier, record) in records {
+            match self.reject_reason(&identifier, now) {
+                Some(reason) => result.rejected.push(Rejected { identifier, record, reason }),
+                None => result.admitted.push((identifier, record)),
+            }
+        }
+        result
+    }
+
+    fn reject_reason(&self, identifier: &MarketIdentifier, now: DateTime<Utc>) -> Option<RejectReason> {
+        let league = canonical(&identifier.league);
+        let operator = canonical(&identifier.operator);
+        if self.excluded_leagues.iter().any(|(scope, excluded)| *excluded == league && scope.as_ref().is_none_or(|scope| *scope == operator)) {
+            return Some(RejectReason::LeagueExcluded { league: identifier.league.clone() });
+        }
+        if !self.included_leagues.is_empty() && !self.included_leagues.contains(&league) {
+            return Some(RejectReason::LeagueNotIncluded { league: identifier.league.clone() });
+        }
+        if self.excluded_markets.contains(&canonical(&identifier.market_type)) {
+            return Some(RejectReason::MarketExcluded { market_type: identifier.market_type.clone() });
+        }
+        let sport = selector(&identifier.sport);
+        if let Some(allowed) = self.families.get(&sport).or_else(|| self.families.get(WILDCARD)) {
+            let Ok(spec) = MarketSpec::parse(&identifier.sport, &identifier.market_type) else {
+                return Some(RejectReason::UnknownMarket { market_type: identifier.market_type.clone() });
+            };
+            if !allowed.contains(&spec.family) {
+                return Some(RejectReason::FamilyNotAllowed { sport: identifier.sport.clone(), family: spec.family });
+            }
+        }
+        if identifier.variant.as_deref().is_some_and(|variant| LIVE_VARIANTS.contains(&canonical(variant).as_str())) {
+            return Some(RejectReason::LiveMarket);
+        }
+        if let Some(cutoff) = self.cutoff {
+            if identifier.event_timestamp - now < cutoff {
+                return Some(RejectReason::PastCutoff { kickoff: identifier.event_timestamp });
+            }
+        }
+        None
+    }
+}
+
+/// Shared `MarketFilter` re-read from `risk.yml` on demand; a broken file keeps the previous filter.
+#[derive(Debug)]
+pub struct MarketFilterHandle {
+    path: PathBuf,
+    current: RwLock<Arc<MarketFilter>>,
+    modified: Mutex<Option<SystemTime>>,
+}
+
+impl MarketFilterHandle {
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, FilterError> {
+        let path = path.into();
+        let modified = modified_at(&path);
+        let filter = MarketFilter::load(&path)?;
+        Ok(Self { path, current: RwLock::new(Arc::new(filter)), modified: Mutex::new(modified) })
+    }
+
+    /// Filter in force; callers keep the `Arc` for a whole batch so a reload never splits one.
+    pub fn current(&self) -> Arc<MarketFilter> {
+        Arc::clone(&self.current.read().unwrap_or_else(|poisoned| poisoned.into_inner()))
+    }
+
+    /// Re-reads the file unconditionally.
+    pub fn reload(&self) -> Result<(), FilterError> {
+        let modified = modified_at(&self.path);
+        let filter = MarketFilter::load(&self.path)?;
+        *self.current.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Arc::new(filter);
+        *self.modified.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = modified;
+        Ok(())
+    }
+
+    /// Re-reads the file when its modification time changed; returns whether a new filter is in force.
+    pub fn reload_if_modified(&self) -> Result<bool, FilterError> {
+        let modified = modified_at(&self.path);
+        if modified == *self.modified.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) {
+            return Ok(false);
+        }
+        self.reload()?;
+        Ok(true)
+    }
+}
+
+fn modified_at(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn canonical(value: &str) -> String {
+    AsciiStrategy.canonicalize(value)
+}
+
+fn selector(value: &str) -> String {
+    let value = value.trim();
+    if value == WILDCARD { value.to_string() } else { canonical(value) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const RISK: &str = "
+markets:
+  included_leagues: [Premier League, NBA, SIM]
+  excluded_leagues: [SX:SIM]
+  excluded_markets: [player-props]
+  families:
+    soccer: [moneyline_3way, total]
+    '*': [moneyline_2way, spread, total]
+  pre_match_cutoff_secs: 300
+  odds_slippage: { default: 0.02 }
+";
+
+    fn identifier(operator: &str, sport: &str, league: &str, market_type: &str) -> MarketIdentifier {
+        MarketIdentifier {
+            operator: operator.into(),
+            sport: sport.into(),
+            league: league.into(),
+            event: "A vs B".into(),
+            market_type: market_type.into(),
+            outcome: "home".into(),
+            event_timestamp: Utc.with_ymd_and_hms(2024, 8, 10, 16, 30, 0).unwrap(),
+            variant: Some("pre".into()),
+            ladder: None,
+        }
+    }
+
+    fn reason(filter: &MarketFilter, identifier: &MarketIdentifier) -> Option<RejectReason> {
+        let now = Utc.with_ymd_and_hms(2024, 8, 10, 12, 0, 0).unwrap();
+        match filter.evaluate(identifier, now) {
+            FilterDecision::Admitted => None,
+            FilterDecision::Rejected(reason) => Some(reason),
+        }
+    }
+
+    #[test]
+    fn rejections_carry_their_reason() {
+        let filter = MarketFilter::from_risk_yaml(RISK).unwrap();
+        assert_eq!(reason(&filter, &identifier("sx", "Soccer", "Premier League", "moneyline")), None);
+        assert_eq!(reason(&filter, &identifier("azuro", "Soccer", "SIM", "moneyline")), None);
+        assert!(matches!(reason(&filter, &identifier("SX", "Soccer", "sim", "moneyline")), Some(RejectReason::LeagueExcluded { .. })));
+        assert!(matches!(reason(&filter, &identifier("sx", "Soccer", "La Liga", "moneyline")), Some(RejectReason::LeagueNotIncluded { .. })));
+        assert!(matches!(reason(&filter, &identifier("sx", "Soccer", "Premier League", "Player Props")), Some(RejectReason::MarketExcluded { .. })));
+        assert_eq!(
+            reason(&filter, &identifier("sx", "Soccer", "Premier League", "double_chance")),
+            Some(RejectReason::FamilyNotAllowed { sport: "Soccer".into(), family: MarketFamily::DoubleChance })
+        );
+        assert!(matches!(reason(&filter, &identifier("sx", "Basketball", "NBA", "corners")), Some(RejectReason::UnknownMarket { .. })));
+        assert_eq!(reason(&filter, &identifier("sx", "Basketball", "NBA", "spread")), None);
+
+        let mut live = identifier("sx", "Soccer", "Premier League", "moneyline");
+        live.variant = Some("Live".into());
+        assert_eq!(reason(&filter, &live), Some(RejectReason::LiveMarket));
+
+        let close = identifier("sx", "Soccer", "Premier League", "moneyline");
+        let now = close.event_timestamp - Duration::minutes(4);
+        assert!(matches!(filter.evaluate(&close, now), FilterDecision::Rejected(RejectReason::PastCutoff { .. })));
+    }
+
+    #[test]
+    fn empty_family_list_is_invalid() {
+        let config = MarketFilterConfig { families: BTreeMap::from([("soccer".to_string(), Vec::new())]), ..Default::default() };
+        assert!(matches!(MarketFilter::new(&config), Err(FilterError::Invalid(_))));
+    }
+}
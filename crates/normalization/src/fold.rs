@@ -0,0 +1,113 @@
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+/// Folds `input` to lower case: NFKD, combining marks dropped, then the transliteration table.
+/// Letters without an entry (CJK, Arabic, …) are kept lower-cased rather than erased.
+pub fn fold_to_ascii(input: &str) -> String {
+    let mut folded = String::with_capacity(input.len());
+    for c in input.nfkd().filter(|c| !is_combining_mark(*c)).flat_map(char::to_lowercase) {
+        match transliterate(c) {
+            Some(ascii) => folded.push_str(ascii),
+            None => folded.push(c),
+        }
+    }
+    folded
+}
+
+/// Letters NFKD leaves untouched: Latin ligatures/strokes, Cyrillic and Greek (lower case only).
+fn transliterate(c: char) -> Option<&'static str> {
+    let ascii = match c {
+        'ß' => "ss",
+        'æ' => "ae",
+        'œ' => "oe",
+        'ø' => "o",
+        'đ' | 'ð' => "d",
+        'ł' => "l",
+        'þ' => "th",
+        'ı' => "i",
+        'ŋ' => "ng",
+        'ħ' => "h",
+        // Cyrillic (Russian, Ukrainian, Serbian)
+        'а' => "a",
+        'б' => "b",
+        'в' => "v",
+        'г' | 'ґ' => "g",
+        'д' => "d",
+        'е' => "e",
+        'є' => "ye",
+        'ж' => "zh",
+        'з' => "z",
+        'и' | 'і' => "i",
+        'ј' => "j",
+        'к' => "k",
+        'л' => "l",
+        'љ' => "lj",
+        'м' => "m",
+        'н' => "n",
+        'њ' => "nj",
+        'о' => "o",
+        'п' => "p",
+        'р' => "r",
+        'с' => "s",
+        'т' => "t",
+        'ћ' => "c",
+        'у' => "u",
+        'ф' => "f",
+        'х' => "kh",
+        'ц' => "ts",
+        'ч' => "ch",
+        'џ' => "dz",
+        'ш' => "sh",
+        'щ' => "shch",
+        'ъ' | 'ь' => "",
+        'ы' => "y",
+        'э' => "e",
+        'ю' => "yu",
+        'я' => "ya",
+        // Greek
+        'α' => "a",
+        'β' => "v",
+        'γ' => "g",
+        'δ' => "d",
+        'ε' => "e",
+        'ζ' => "z",
+        'η' => "i",
+        'θ' => "th",
+        'ι' => "i",
+        'κ' => "k",
+        'λ' => "l",
+        'μ' => "m",
+        'ν' => "n",
+        'ξ' => "x",
+        'ο' => "o",
+        'π' => "p",
+        'ρ' => "r",
+        'σ' | 'ς' => "s",
+        'τ' => "t",
+        'υ' => "y",
+        'φ' => "f",
+        'χ' => "ch",
+        'ψ' => "ps",
+        'ω' => "o",
+        _ => return None,
+    };
+    Some(ascii)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folds_diacritics_ligatures_and_scripts() {
+        assert_eq!(fold_to_ascii("Atlético Madrid"), "atletico madrid");
+        assert_eq!(fold_to_ascii("Bayern München"), "bayern munchen");
+        assert_eq!(fold_to_ascii("Łódź"), "lodz");
+        assert_eq!(fold_to_ascii("Straße"), "strasse");
+        assert_eq!(fold_to_ascii("ＰＳＧ"), "psg");
+        assert_eq!(fold_to_ascii("Зенит"), "zenit");
+        assert_eq!(fold_to_ascii("Олимпиакос"), "olimpiakos");
+        assert_eq!(fold_to_ascii("Ολυμπιακός"), "olympiakos");
+        assert_eq!(fold_to_ascii("浦和"), "浦和");
+    }
+}
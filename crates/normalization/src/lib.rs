@@ -1,7 +1,42 @@
 //! Normalization toolkit for cross-operator market identifiers.
 
+pub mod aliases;
+pub mod catalog;
 pub mod dedup;
+pub mod filter;
+pub mod fold;
+pub mod mapping_store;
 pub mod market_uid;
+pub mod migration;
+pub mod pairing;
+pub mod rulepack;
+pub mod seed;
+pub mod streaming;
+pub mod taxonomy;
 
-pub use dedup::{deduplicate, DedupKey, DedupResult, MarketRecord};
-pub use market_uid::{MarketIdentifier, MarketUid, MarketUidError};
+pub use aliases::{AliasDictionary, AliasError, AliasKind, AliasResolver, FuzzyConfig, Resolution, ReviewItem, ReviewQueue};
+pub use catalog::{CatalogEntry, CatalogError, MarketCatalog};
+pub use dedup::{
+    deduplicate, deduplicate_with, deduplicate_with_migration, BestOdds, Choice, DedupKey, DedupPolicy, DedupResult, DropReason,
+    Dropped, FirstSeen, LatestTimestamp, MarketRecord, SourcePriority,
+};
+pub use filter::{
+    FilterDecision, FilterError, FilterResult, MarketFilter, MarketFilterConfig, MarketFilterHandle, RejectReason, Rejected,
+};
+pub use fold::fold_to_ascii;
+pub use market_uid::{
+    AsciiStrategy, FingerprintRegistry, FingerprintStrategy, MarketIdentifier, MarketKey, MarketUid, MarketUidError,
+    UidVersion, UnicodeStrategy,
+};
+pub use mapping_store::{
+    MappingEntry, MappingOverrides, MappingSource, MappingStatus, MappingStore, MappingStoreError, AUTOMATIC_DECIDER,
+};
+pub use migration::{build_mapping, read_mapping, read_seed, write_mapping, MigrationError, SeedEntry, UidMapping, UidMigration};
+pub use pairing::{
+    pair_markets, pair_markets_with, pair_markets_with_overrides, AmbiguousMatch, MarketPair, NoOverrides, PairLeg, PairOverrides,
+    PairingOptions, PairingResult,
+};
+pub use rulepack::{compatible, HandicapSettlement, Overtime, Postponement, RuleConflict, Rulepack, RulepackError, SettlementRules};
+pub use seed::{SeedCatalogue, SeedError, SeedIssue, SeedReport};
+pub use streaming::{Clock, ManualClock, StreamDecision, StreamStats, StreamingConfig, StreamingDeduplicator, SystemClock};
+pub use taxonomy::{MarketFamily, MarketSpec, Outcome, Period, Side, TaxonomyError};
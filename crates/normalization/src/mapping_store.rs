@@ -0,0 +1,359 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::io::{Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::market_uid::{MarketUid, MarketUidError};
+use crate::pairing::{MarketPair, PairOverrides};
+
+/// `decided_by` of entries written by `record_pairs`.
+pub const AUTOMATIC_DECIDER: &str = "pairing";
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS market_mappings (
+    left_uid   TEXT NOT NULL,
+    right_uid  TEXT NOT NULL,
+    source     TEXT NOT NULL CHECK (source IN ('automatic', 'manual')),
+    status     TEXT NOT NULL CHECK (status IN ('confirmed', 'rejected')),
+    decided_by TEXT NOT NULL,
+    decided_at TEXT NOT NULL,
+    note       TEXT,
+    PRIMARY KEY (left_uid, right_uid, source)
+);
+CREATE INDEX IF NOT EXISTS market_mappings_right_uid ON market_mappings (right_uid);
+";
+
+#[derive(Debug, Error)]
+pub enum MappingStoreError {
+    #[error("sqlite: {0}")]
+    Sqlite(String),
+    #[error("invalid csv: {0}")]
+    Csv(String),
+    #[error(transparent)]
+    Uid(#[from] MarketUidError),
+    #[error("invalid stored value `{0}`")]
+    InvalidValue(String),
+    #[error("cannot map `{0}` to itself")]
+    SelfMapping(MarketUid),
+}
+
+impl From<rusqlite::Error> for MappingStoreError {
+    fn from(err: rusqlite::Error) -> Self {
+        MappingStoreError::Sqlite(err.to_string())
+    }
+}
+
+impl From<csv::Error> for MappingStoreError {
+    fn from(err: csv::Error) -> Self {
+        MappingStoreError::Csv(err.to_string())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MappingStatus {
+    Confirmed,
+    Rejected,
+}
+
+/// Manual entries win over automatic ones for the same pair.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MappingSource {
+    Automatic,
+    Manual,
+}
+
+macro_rules! text_enum {
+    ($name:ident { $($variant:ident => $text:literal),+ $(,)? }) => {
+        impl $name {
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $($name::$variant => $text),+
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = MappingStoreError;
+
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                match value {
+                    $($text => Ok($name::$variant),)+
+                    other => Err(MappingStoreError::InvalidValue(other.to_string())),
+                }
+            }
+        }
+    };
+}
+
+text_enum!(MappingStatus { Confirmed => "confirmed", Rejected => "rejected" });
+text_enum!(MappingSource { Automatic => "automatic", Manual => "manual" });
+
+/// One decision about a pair of market UIDs; the pair is unordered and stored with `left_uid < right_uid`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MappingEntry {
+    pub left_uid: MarketUid,
+    pub right_uid: MarketUid,
+    pub source: MappingSource,
+    pub status: MappingStatus,
+    pub decided_by: String,
+    pub decided_at: DateTime<Utc>,
+    pub note: Option<String>,
+}
+
+impl MappingEntry {
+    pub fn manual(
+        a: MarketUid,
+        b: MarketUid,
+        status: MappingStatus,
+        decided_by: impl Into<String>,
+        decided_at: DateTime<Utc>,
+    ) -> Self {
+        let (left_uid, right_uid) = ordered(a, b);
+        Self { left_uid, right_uid, source: MappingSource::Manual, status, decided_by: decided_by.into(), decided_at, note: None }
+    }
+
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.note = Some(note.into());
+        self
+    }
+}
+
+/// Text columns of one `market_mappings` row, validated into a `MappingEntry` after the read.
+struct StoredRow {
+    left_uid: String,
+    right_uid: String,
+    source: String,
+    status: String,
+    decided_by: String,
+    decided_at: String,
+    note: Option<String>,
+}
+
+impl StoredRow {
+    fn read(row: &Row<'_>) -> rusqlite::Result<Self> {
+        Ok(Self {
+            left_uid: row.get("left_uid")?,
+            right_uid: row.get("right_uid")?,
+            source: row.get("source")?,
+            status: row.get("status")?,
+            decided_by: row.get("decided_by")?,
+            decided_at: row.get("decided_at")?,
+            note: row.get("note")?,
+        })
+    }
+
+    fn into_entry(self) -> Result<MappingEntry, MappingStoreError> {
+        let decided_at = DateTime::parse_from_rfc3339(&self.decided_at)
+            .map_err(|_| MappingStoreError::InvalidValue(self.decided_at.clone()))?
+            .with_timezone(&Utc);
+        Ok(MappingEntry {
+            left_uid: MarketUid::parse(&self.left_uid)?,
+            right_uid: MarketUid::parse(&self.right_uid)?,
+            source: self.source.parse()?,
+            status: self.status.parse()?,
+            decided_by: self.decided_by,
+            decided_at,
+            note: self.note,
+        })
+    }
+}
+
+/// Embedded SQLite store of confirmed and rejected cross-venue pairs.
+#[derive(Debug)]
+pub struct MappingStore {
+    connection: Connection,
+}
+
+impl MappingStore {
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, MappingStoreError> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, MappingStoreError> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, MappingStoreError> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self { connection })
+    }
+
+    /// Inserts or replaces the entry for its pair and source.
+    pub fn record(&self, entry: &MappingEntry) -> Result<(), MappingStoreError> {
+        upsert(&self.connection, entry)
+    }
+
+    pub fn confirm(&self, a: &MarketUid, b: &MarketUid, decided_by: &str, at: DateTime<Utc>) -> Result<(), MappingStoreError> {
+        self.record(&MappingEntry::manual(a.clone(), b.clone(), MappingStatus::Confirmed, decided_by, at))
+    }
+
+    pub fn reject(&self, a: &MarketUid, b: &MarketUid, decided_by: &str, at: DateTime<Utc>) -> Result<(), MappingStoreError> {
+        self.record(&MappingEntry::manual(a.clone(), b.clone(), MappingStatus::Rejected, decided_by, at))
+    }
+
+    /// Drops the manual decision on a pair; automatic history is kept. Returns whether one existed.
+    pub fn clear_override(&self, a: &MarketUid, b: &MarketUid) -> Result<bool, MappingStoreError> {
+        let (left, right) = ordered(a.clone(), b.clone());
+        let removed = self.connection.execute(
+            "DELETE FROM market_mappings WHERE left_uid = ?1 AND right_uid = ?2 AND source = 'manual'",
+            params![left.as_str(), right.as_str()],
+        )?;
+        Ok(removed > 0)
+    }
+
+    /// Stores the pairs found by automatic matching, for review; manual entries are left untouched.
+    pub fn record_pairs<T>(&self, pairs: &[MarketPair<T>], at: DateTime<Utc>) -> Result<usize, MappingStoreError> {
+        let mut recorded = 0;
+        for pair in pairs {
+            let (a, b) = (pair.left.record.key.uid.clone(), pair.right.record.key.uid.clone());
+            let (left_uid, right_uid) = ordered(a, b);
+            self.record(&MappingEntry {
+                left_uid,
+                right_uid,
+                source: MappingSource::Automatic,
+                status: MappingStatus::Confirmed,
+                decided_by: AUTOMATIC_DECIDER.to_string(),
+                decided_at: at,
+                note: None,
+            })?;
+            recorded += 1;
+        }
+        Ok(recorded)
+    }
+
+    /// Entry in force for a pair: the manual one if present, else the automatic one.
+    pub fn effective(&self, a: &MarketUid, b: &MarketUid) -> Result<Option<MappingEntry>, MappingStoreError> {
+        let (left, right) = ordered(a.clone(), b.clone());
+        self.connection
+            .query_row(
+                "SELECT * FROM market_mappings WHERE left_uid = ?1 AND right_uid = ?2
+                 ORDER BY CASE source WHEN 'manual' THEN 0 ELSE 1 END LIMIT 1",
+                params![left.as_str(), right.as_str()],
+                StoredRow::read,
+            )
+            .optional()?
+            .map(StoredRow::into_entry)
+            .transpose()
+    }
+
+    /// Every entry involving `uid`, on either side.
+    pub fn entries_for(&self, uid: &MarketUid) -> Result<Vec<MappingEntry>, MappingStoreError> {
+        self.query("SELECT * FROM market_mappings WHERE left_uid = ?1 OR right_uid = ?1 ORDER BY left_uid, right_uid, source", [uid.as_str()])
+    }
+
+    pub fn entries(&self) -> Result<Vec<MappingEntry>, MappingStoreError> {
+        self.query("SELECT * FROM market_mappings ORDER BY left_uid, right_uid, source", [])
+    }
+
+    /// Snapshot of the manual decisions, to pass to `pair_markets_with_overrides`.
+    pub fn overrides(&self) -> Result<MappingOverrides, MappingStoreError> {
+        let manual = self.query("SELECT * FROM market_mappings WHERE source = 'manual'", [])?;
+        Ok(MappingOverrides::from_entries(&manual))
+    }
+
+    /// CSV with the `MappingEntry` columns, sorted by pair, for review.
+    pub fn export(&self, writer: impl Write) -> Result<usize, MappingStoreError> {
+        let entries = self.entries()?;
+        let mut writer = csv::Writer::from_writer(writer);
+        for entry in &entries {
+            writer.serialize(entry)?;
+        }
+        writer.flush().map_err(|err| MappingStoreError::Csv(err.to_string()))?;
+        Ok(entries.len())
+    }
+
+    /// Loads an `export` file in one transaction; entries replace stored ones with the same pair and source.
+    pub fn import(&mut self, reader: impl Read) -> Result<usize, MappingStoreError> {
+        let entries: Vec<MappingEntry> = csv::Reader::from_reader(reader).deserialize().collect::<Result<_, _>>()?;
+        let transaction = self.connection.transaction()?;
+        for entry in &entries {
+            upsert(&transaction, entry)?;
+        }
+        transaction.commit()?;
+        Ok(entries.len())
+    }
+
+    fn query(&self, sql: &str, params: impl rusqlite::Params) -> Result<Vec<MappingEntry>, MappingStoreError> {
+        let mut statement = self.connection.prepare(sql)?;
+        let rows = statement.query_map(params, StoredRow::read)?;
+        rows.map(|row| row?.into_entry()).collect()
+    }
+}
+
+/// In-memory view of the manual decisions, implementing `PairOverrides`.
+#[derive(Clone, Debug, Default)]
+pub struct MappingOverrides {
+    confirmed: HashMap<MarketUid, Vec<MarketUid>>,
+    rejected: HashSet<(MarketUid, MarketUid)>,
+}
+
+impl MappingOverrides {
+    pub fn from_entries<'a>(entries: impl IntoIterator<Item = &'a MappingEntry>) -> Self {
+        let mut overrides = Self::default();
+        for entry in entries.into_iter().filter(|entry| entry.source == MappingSource::Manual) {
+            let (left, right) = ordered(entry.left_uid.clone(), entry.right_uid.clone());
+            match entry.status {
+                MappingStatus::Confirmed => {
+                    overrides.confirmed.entry(left.clone()).or_default().push(right.clone());
+                    overrides.confirmed.entry(right).or_default().push(left);
+                }
+                MappingStatus::Rejected => {
+                    overrides.rejected.insert((left, right));
+                }
+            }
+        }
+        overrides
+    }
+}
+
+impl PairOverrides for MappingOverrides {
+    fn confirmed(&self, uid: &MarketUid) -> &[MarketUid] {
+        self.confirmed.get(uid).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    fn is_rejected(&self, left: &MarketUid, right: &MarketUid) -> bool {
+        self.rejected.contains(&ordered(left.clone(), right.clone()))
+    }
+}
+
+fn upsert(connection: &Connection, entry: &MappingEntry) -> Result<(), MappingStoreError> {
+    if entry.left_uid == entry.right_uid {
+        return Err(MappingStoreError::SelfMapping(entry.left_uid.clone()));
+    }
+    let (left, right) = ordered(entry.left_uid.clone(), entry.right_uid.clone());
+    connection.execute(
+        "INSERT INTO market_mappings (left_uid, right_uid, source, status, decided_by, decided_at, note)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+         ON CONFLICT (left_uid, right_uid, source) DO UPDATE SET
+             status = excluded.status, decided_by = excluded.decided_by,
+             decided_at = excluded.decided_at, note = excluded.note",
+        params![
+            left.as_str(),
+            right.as_str(),
+            entry.source.as_str(),
+            entry.status.as_str(),
+            entry.decided_by,
+            entry.decided_at.to_rfc3339(),
+            entry.note,
+        ],
+    )?;
+    Ok(())
+}
+
+fn ordered(a: MarketUid, b: MarketUid) -> (MarketUid, MarketUid) {
+    if a <= b { (a, b) } else { (b, a) }
+}
@@ -1,11 +1,155 @@
 use chrono::{DateTime, Timelike, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
 use thiserror::Error;
 
+use crate::aliases::AliasResolver;
+use crate::fold::fold_to_ascii;
+
 const MARKET_UID_PREFIX: &str = "muid";
-const MARKET_UID_VERSION: &str = "v1";
+const MARKET_KEY_PREFIX: &str = "mkey";
+const HASH_LEN: usize = 24;
+
+/// Fingerprint scheme version, rendered `v<n>` in fingerprints and UIDs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct UidVersion(u16);
+
+impl UidVersion {
+    /// ASCII-only: every other character becomes a separator.
+    pub const V1: Self = Self(1);
+    /// Unicode-aware: NFKD diacritic folding, Cyrillic/Greek transliteration, other letters kept.
+    pub const V2: Self = Self(2);
+
+    pub const fn new(number: u16) -> Self {
+        Self(number)
+    }
+
+    pub fn number(self) -> u16 {
+        self.0
+    }
+}
+
+/// `V1` stays the default so seeded UIDs keep reproducing.
+impl Default for UidVersion {
+    fn default() -> Self {
+        Self::V1
+    }
+}
+
+impl fmt::Display for UidVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "v{}", self.0)
+    }
+}
+
+impl FromStr for UidVersion {
+    type Err = MarketUidError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        value
+            .strip_prefix('v')
+            .and_then(|number| number.parse().ok())
+            .map(Self)
+            .ok_or_else(|| MarketUidError::InvalidVersion(value.to_string()))
+    }
+}
+
+impl TryFrom<String> for UidVersion {
+    type Error = MarketUidError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<UidVersion> for String {
+    fn from(version: UidVersion) -> Self {
+        version.to_string()
+    }
+}
+
+/// Canonicalisation applied to every fingerprint segment of one UID version.
+pub trait FingerprintStrategy: Send + Sync {
+    fn canonicalize(&self, input: &str) -> String;
+}
+
+/// v1 strategy: ASCII alphanumerics only.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AsciiStrategy;
+
+impl FingerprintStrategy for AsciiStrategy {
+    fn canonicalize(&self, input: &str) -> String {
+        canonicalize(input)
+    }
+}
+
+/// v2 strategy: `fold_to_ascii` then Unicode alphanumerics.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UnicodeStrategy;
+
+impl FingerprintStrategy for UnicodeStrategy {
+    fn canonicalize(&self, input: &str) -> String {
+        canonicalize_unicode(input)
+    }
+}
+
+/// Fingerprint strategies by UID version; `standard()` knows v1 and v2.
+#[derive(Clone, Default)]
+pub struct FingerprintRegistry {
+    strategies: BTreeMap<UidVersion, Arc<dyn FingerprintStrategy>>,
+}
+
+impl FingerprintRegistry {
+    pub fn standard() -> &'static FingerprintRegistry {
+        static STANDARD: OnceLock<FingerprintRegistry> = OnceLock::new();
+        STANDARD.get_or_init(|| {
+            FingerprintRegistry::default()
+                .register(UidVersion::V1, AsciiStrategy)
+                .register(UidVersion::V2, UnicodeStrategy)
+        })
+    }
+
+    pub fn register(mut self, version: UidVersion, strategy: impl FingerprintStrategy + 'static) -> Self {
+        self.strategies.insert(version, Arc::new(strategy));
+        self
+    }
+
+    pub fn strategy(&self, version: UidVersion) -> Result<&dyn FingerprintStrategy, MarketUidError> {
+        self.strategies
+            .get(&version)
+            .map(|strategy| strategy.as_ref())
+            .ok_or(MarketUidError::UnsupportedVersion(version))
+    }
+
+    pub fn versions(&self) -> impl Iterator<Item = UidVersion> + '_ {
+        self.strategies.keys().copied()
+    }
+
+    pub fn latest(&self) -> Option<UidVersion> {
+        self.strategies.keys().next_back().copied()
+    }
+
+    pub fn uid(&self, identifier: &MarketIdentifier, version: UidVersion) -> Result<MarketUid, MarketUidError> {
+        let fingerprint = identifier.fingerprint_with(self.strategy(version)?, version)?;
+        Ok(MarketUid { value: format!("{MARKET_UID_PREFIX}-{version}-{}", short_hash(&fingerprint)), version })
+    }
+
+    pub fn market_key(&self, identifier: &MarketIdentifier, version: UidVersion) -> Result<MarketKey, MarketUidError> {
+        let fingerprint = identifier.market_fingerprint_with(self.strategy(version)?, version)?;
+        Ok(MarketKey(format!("{MARKET_KEY_PREFIX}-{version}-{}", short_hash(&fingerprint))))
+    }
+}
+
+impl fmt::Debug for FingerprintRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FingerprintRegistry").field("versions", &self.strategies.keys().collect::<Vec<_>>()).finish()
+    }
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct MarketIdentifier {
@@ -15,77 +159,209 @@ pub struct MarketIdentifier {
 }
 
 impl MarketIdentifier {
+    /// v1 fingerprint; see `canonical_fingerprint_versioned` for later versions.
     pub fn canonical_fingerprint(&self) -> Result<String, MarketUidError> {
-        let operator = normalize_required(&self.operator, "operator")?;
-        let sport = normalize_required(&self.sport, "sport")?;
-        let league = normalize_required(&self.league, "league")?;
-        let event = normalize_required(&self.event, "event")?;
-        let market_type = normalize_required(&self.market_type, "market_type")?;
-        let outcome = normalize_required(&self.outcome, "outcome")?;
-        let ladder = normalize_optional(self.ladder.as_deref());
-        let variant = normalize_optional(self.variant.as_deref());
+        self.canonical_fingerprint_versioned(UidVersion::V1)
+    }
+
+    pub fn canonical_fingerprint_versioned(&self, version: UidVersion) -> Result<String, MarketUidError> {
+        self.fingerprint_with(FingerprintRegistry::standard().strategy(version)?, version)
+    }
+
+    /// Operator-agnostic fingerprint of the market (no operator, outcome or venue ladder).
+    pub fn market_fingerprint(&self, version: UidVersion) -> Result<String, MarketUidError> {
+        self.market_fingerprint_with(FingerprintRegistry::standard().strategy(version)?, version)
+    }
+
+    fn fingerprint_with(&self, strategy: &dyn FingerprintStrategy, version: UidVersion) -> Result<String, MarketUidError> {
+        let operator = normalize_required(&self.operator, "operator", strategy)?;
+        let sport = normalize_required(&self.sport, "sport", strategy)?;
+        let league = normalize_required(&self.league, "league", strategy)?;
+        let event = normalize_required(&self.event, "event", strategy)?;
+        let market_type = normalize_required(&self.market_type, "market_type", strategy)?;
+        let outcome = normalize_required(&self.outcome, "outcome", strategy)?;
+        let ladder = normalize_optional(self.ladder.as_deref(), strategy);
+        let variant = normalize_optional(self.variant.as_deref(), strategy);
         let event_time = truncate_timestamp(self.event_timestamp);
 
         Ok(format!(
-            "{MARKET_UID_PREFIX}|{MARKET_UID_VERSION}|{operator}|{sport}|{league}|{event}|{market_type}|{variant}|{ladder}|{event_time}|{outcome}"
+            "{MARKET_UID_PREFIX}|{version}|{operator}|{sport}|{league}|{event}|{market_type}|{variant}|{ladder}|{event_time}|{outcome}"
         ))
     }
+
+    fn market_fingerprint_with(&self, strategy: &dyn FingerprintStrategy, version: UidVersion) -> Result<String, MarketUidError> {
+        let untimed = self.untimed_fingerprint_with(strategy, version)?;
+        Ok(format!("{untimed}|{}", truncate_timestamp(self.event_timestamp)))
+    }
+
+    /// Market fingerprint without the kick-off minute, used to match events across venues within a tolerance.
+    pub(crate) fn untimed_market_fingerprint(&self, version: UidVersion) -> Result<String, MarketUidError> {
+        self.untimed_fingerprint_with(FingerprintRegistry::standard().strategy(version)?, version)
+    }
+
+    fn untimed_fingerprint_with(&self, strategy: &dyn FingerprintStrategy, version: UidVersion) -> Result<String, MarketUidError> {
+        let sport = normalize_required(&self.sport, "sport", strategy)?;
+        let league = normalize_required(&self.league, "league", strategy)?;
+        let event = normalize_required(&self.event, "event", strategy)?;
+        let market_type = normalize_required(&self.market_type, "market_type", strategy)?;
+        let variant = normalize_optional(self.variant.as_deref(), strategy);
+
+        Ok(format!("{MARKET_KEY_PREFIX}|{version}|{sport}|{league}|{event}|{market_type}|{variant}"))
+    }
+
+    /// Kick-off truncated to the minute, as fingerprinted.
+    pub fn kickoff_minute(&self) -> DateTime<Utc> {
+        truncate_to_minute(self.event_timestamp)
+    }
+
+    /// League and teams rewritten to their dictionary names; call before fingerprinting.
+    pub fn with_aliases(&self, resolver: &AliasResolver) -> MarketIdentifier {
+        resolver.apply(self)
+    }
+
+    pub fn canonical_operator(&self) -> Result<String, MarketUidError> {
+        normalize_required(&self.operator, "operator", &AsciiStrategy)
+    }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct MarketUid(String);
+/// `muid-v<n>-<24 hex>`; only built from an identifier or a validated string.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct MarketUid {
+    value: String,
+    /// Parsed once from `value` so `version()` never re-parses.
+    version: UidVersion,
+}
 
 impl MarketUid {
+    /// v1 UID, the format every stored UID still uses; see `from_identifier_versioned` for later versions.
     pub fn from_identifier(identifier: &MarketIdentifier) -> Result<Self, MarketUidError> {
-        let fingerprint = identifier.canonical_fingerprint()?;
-        let mut hasher = Sha256::new();
-        hasher.update(fingerprint.as_bytes());
-        let digest = hasher.finalize();
-        let hash = hex::encode(digest);
-        let value = format!("{MARKET_UID_PREFIX}-{MARKET_UID_VERSION}-{}", &hash[..24]);
-        Ok(Self(value))
+        Self::from_identifier_versioned(identifier, UidVersion::V1)
+    }
+
+    pub fn from_identifier_versioned(identifier: &MarketIdentifier, version: UidVersion) -> Result<Self, MarketUidError> {
+        FingerprintRegistry::standard().uid(identifier, version)
+    }
+
+    pub fn parse(value: &str) -> Result<Self, MarketUidError> {
+        let invalid = || MarketUidError::InvalidUid(value.to_string());
+        let mut parts = value.splitn(3, '-');
+        let (Some(MARKET_UID_PREFIX), Some(version), Some(hash)) = (parts.next(), parts.next(), parts.next()) else {
+            return Err(invalid());
+        };
+        let version = version.parse::<UidVersion>().map_err(|_| invalid())?;
+        if hash.len() != HASH_LEN || !hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+            return Err(invalid());
+        }
+        Ok(Self { value: value.to_string(), version })
     }
 
     pub fn as_str(&self) -> &str {
-        &self.0
+        &self.value
+    }
+
+    pub fn prefix(&self) -> &str {
+        MARKET_UID_PREFIX
+    }
+
+    pub fn version(&self) -> UidVersion {
+        self.version
+    }
+
+    pub fn hash(&self) -> &str {
+        &self.value[self.value.len() - HASH_LEN..]
+    }
+}
+
+impl FromStr for MarketUid {
+    type Err = MarketUidError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::parse(value)
+    }
+}
+
+impl TryFrom<String> for MarketUid {
+    type Error = MarketUidError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<MarketUid> for String {
+    fn from(uid: MarketUid) -> Self {
+        uid.value
     }
 }
 
 impl fmt::Display for MarketUid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.value)
+    }
+}
+
+/// Same market across operators: shared by every outcome and venue of one event market.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct MarketKey(String);
+
+impl MarketKey {
+    pub fn from_identifier(identifier: &MarketIdentifier, version: UidVersion) -> Result<Self, MarketUidError> {
+        FingerprintRegistry::standard().market_key(identifier, version)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for MarketKey {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(&self.0)
     }
 }
 
+fn short_hash(fingerprint: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(fingerprint.as_bytes());
+    let hash = hex::encode(hasher.finalize());
+    hash[..HASH_LEN].to_string()
+}
+
 #[derive(Debug, Error)]
 pub enum MarketUidError {
     #[error("missing required field `{0}` for market UID generation")]
     MissingField(&'static str),
+    #[error("malformed market UID `{0}`")]
+    InvalidUid(String),
+    #[error("malformed UID version `{0}`")]
+    InvalidVersion(String),
+    #[error("no fingerprint strategy registered for {0}")]
+    UnsupportedVersion(UidVersion),
 }
 
-fn normalize_required(value: &str, field: &'static str) -> Result<String, MarketUidError> {
+fn normalize_required(value: &str, field: &'static str, strategy: &dyn FingerprintStrategy) -> Result<String, MarketUidError> {
     let cleaned = value.trim();
     if cleaned.is_empty() {
         Err(MarketUidError::MissingField(field))
     } else {
-        Ok(canonicalize(cleaned))
+        Ok(strategy.canonicalize(cleaned))
     }
 }
 
-fn normalize_optional(value: Option<&str>) -> String {
+fn normalize_optional(value: Option<&str>, strategy: &dyn FingerprintStrategy) -> String {
     value
-        .map(|v| canonicalize(v.trim()))
+        .map(|v| strategy.canonicalize(v.trim()))
         .filter(|v| !v.is_empty())
         .unwrap_or_else(|| "na".to_string())
 }
 
 fn truncate_timestamp(timestamp: DateTime<Utc>) -> String {
-    timestamp
-        .with_second(0)
-        .and_then(|dt| dt.with_nanosecond(0))
-        .unwrap_or(timestamp)
-        .format("%Y%m%dT%H%MZ")
-        .to_string()
+    truncate_to_minute(timestamp).format("%Y%m%dT%H%MZ").to_string()
+}
+
+fn truncate_to_minute(timestamp: DateTime<Utc>) -> DateTime<Utc> {
+    timestamp.with_second(0).and_then(|dt| dt.with_nanosecond(0)).unwrap_or(timestamp)
 }
 
 fn canonicalize(input: &str) -> String {
@@ -100,6 +376,18 @@ fn canonicalize(input: &str) -> String {
         .join("_")
 }
 
+fn canonicalize_unicode(input: &str) -> String {
+    let normalized: String = fold_to_ascii(input)
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+    normalized
+        .split_whitespace()
+        .map(str::to_string)
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -115,4 +403,60 @@ mod tests {
         let error = MarketUid::from_identifier(&missing_operator).unwrap_err();
         assert!(matches!(error, MarketUidError::MissingField("operator")));
     }
+
+    #[test]
+    fn market_key_ignores_operator_outcome_and_ladder() {
+        let sx = MarketIdentifier { operator: "sx".into(), sport: "Soccer".into(), league: "Premier League".into(), event: "Arsenal vs Chelsea".into(), market_type: "moneyline".into(), outcome: "home".into(), event_timestamp: Utc.with_ymd_and_hms(2024, 5, 1, 18, 30, 0).unwrap(), variant: Some("pre".into()), ladder: Some("0.01".into()) };
+        let azuro = MarketIdentifier { operator: "azuro".into(), outcome: "away".into(), ladder: None, ..sx.clone() };
+        assert_ne!(MarketUid::from_identifier(&sx).unwrap(), MarketUid::from_identifier(&azuro).unwrap());
+        let key = MarketKey::from_identifier(&sx, UidVersion::V1).unwrap();
+        assert_eq!(key, MarketKey::from_identifier(&azuro, UidVersion::V1).unwrap());
+        assert!(key.as_str().starts_with("mkey-v1-"));
+    }
+
+    #[test]
+    fn v2_folds_unicode_while_v1_is_unchanged() {
+        let latin = MarketIdentifier { operator: "sx".into(), sport: "Soccer".into(), league: "La Liga".into(), event: "Atlético Madrid vs Sevilla".into(), market_type: "moneyline".into(), outcome: "home".into(), event_timestamp: Utc.with_ymd_and_hms(2024, 9, 29, 19, 0, 0).unwrap(), variant: None, ladder: None };
+        assert!(latin.canonical_fingerprint().unwrap().contains("|atl_tico_madrid_vs_sevilla|"));
+        assert!(latin.canonical_fingerprint_versioned(UidVersion::V2).unwrap().contains("|v2|sx|soccer|la_liga|atletico_madrid_vs_sevilla|"));
+        let ascii = MarketIdentifier { event: "Atletico Madrid vs Sevilla".into(), ..latin.clone() };
+        assert_eq!(MarketUid::from_identifier_versioned(&latin, UidVersion::V2).unwrap(), MarketUid::from_identifier_versioned(&ascii, UidVersion::V2).unwrap());
+        assert!(MarketUid::from_identifier_versioned(&latin, UidVersion::V2).unwrap().as_str().starts_with("muid-v2-"));
+
+        let zenit = MarketIdentifier { event: "Зенит vs Спартак".into(), ..latin.clone() };
+        let cska = MarketIdentifier { event: "ЦСКА vs Локомотив".into(), ..latin };
+        assert_eq!(MarketUid::from_identifier(&zenit).unwrap(), MarketUid::from_identifier(&cska).unwrap(), "v1 collapses Cyrillic");
+        assert_ne!(MarketUid::from_identifier_versioned(&zenit, UidVersion::V2).unwrap(), MarketUid::from_identifier_versioned(&cska, UidVersion::V2).unwrap());
+    }
+
+    #[test]
+    fn market_uid_parses_back_into_parts() {
+        let identifier = MarketIdentifier { operator: "sx".into(), sport: "Soccer".into(), league: "Premier League".into(), event: "Arsenal vs Chelsea".into(), market_type: "moneyline".into(), outcome: "home".into(), event_timestamp: Utc.with_ymd_and_hms(2024, 8, 10, 16, 30, 0).unwrap(), variant: Some("pre".into()), ladder: None };
+        let uid = MarketUid::from_identifier_versioned(&identifier, UidVersion::V2).unwrap();
+        let parsed: MarketUid = uid.as_str().parse().unwrap();
+        assert_eq!(parsed, uid);
+        assert_eq!(parsed.prefix(), "muid");
+        assert_eq!(parsed.version(), UidVersion::V2);
+        assert_eq!(parsed.hash().len(), 24);
+
+        for invalid in ["muid-v1-xyz", "mkey-v1-b05bf41737061f9a2d1595d7", "muid-1-b05bf41737061f9a2d1595d7", "muid-v1-B05BF41737061F9A2D1595D7"] {
+            assert!(matches!(MarketUid::parse(invalid), Err(MarketUidError::InvalidUid(_))), "{invalid}");
+        }
+        let yaml = serde_yaml::to_string(&parsed).unwrap();
+        assert_eq!(serde_yaml::from_str::<MarketUid>(&yaml).unwrap(), parsed);
+        assert!(serde_yaml::from_str::<MarketUid>("muid-v1-nothex").is_err());
+    }
+
+    #[test]
+    fn registry_rejects_unknown_versions() {
+        let identifier = MarketIdentifier { operator: "sx".into(), sport: "Soccer".into(), league: "Ligue 1".into(), event: "PSG vs Marseille".into(), market_type: "moneyline".into(), outcome: "home".into(), event_timestamp: Utc.with_ymd_and_hms(2024, 9, 22, 18, 45, 0).unwrap(), variant: None, ladder: None };
+        let registry = FingerprintRegistry::standard();
+        assert_eq!(registry.versions().collect::<Vec<_>>(), vec![UidVersion::V1, UidVersion::V2]);
+        assert_eq!(registry.latest(), Some(UidVersion::V2));
+        let err = MarketUid::from_identifier_versioned(&identifier, UidVersion::new(3)).unwrap_err();
+        assert!(matches!(err, MarketUidError::UnsupportedVersion(version) if version.number() == 3));
+
+        let custom = FingerprintRegistry::default().register(UidVersion::new(3), AsciiStrategy);
+        assert_eq!(custom.uid(&identifier, UidVersion::new(3)).unwrap().version(), UidVersion::new(3));
+    }
 }
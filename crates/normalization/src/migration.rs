@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+use std::io::{Read, Write};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::market_uid::{MarketIdentifier, MarketUid, MarketUidError, UidVersion};
+
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    #[error("invalid csv: {0}")]
+    Csv(String),
+    #[error(transparent)]
+    Uid(#[from] MarketUidError),
+    #[error("seed row {row}: recorded `{recorded}` but {version} yields `{computed}`")]
+    SeedMismatch { row: usize, version: UidVersion, recorded: MarketUid, computed: MarketUid },
+    #[error("UID `{uid}` maps to both `{first}` and `{second}`")]
+    Conflict { uid: MarketUid, first: MarketUid, second: MarketUid },
+}
+
+impl From<csv::Error> for MigrationError {
+    fn from(err: csv::Error) -> Self {
+        MigrationError::Csv(err.to_string())
+    }
+}
+
+/// One row of `data/market_uid_seed.csv`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SeedEntry {
+    pub identifier: MarketIdentifier,
+    pub market_uid: MarketUid,
+}
+
+/// Row of the seed CSV kept as text, so callers decide how to surface broken timestamps and UIDs.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SeedRow {
+    pub(crate) operator: String,
+    pub(crate) sport: String,
+    pub(crate) league: String,
+    pub(crate) event: String,
+    pub(crate) market_type: String,
+    pub(crate) outcome: String,
+    pub(crate) variant: Option<String>,
+    pub(crate) ladder: Option<String>,
+    pub(crate) event_timestamp: String,
+    pub(crate) market_uid: String,
+}
+
+impl SeedRow {
+    /// `None` when `event_timestamp` is not RFC 3339.
+    pub(crate) fn identifier(&self) -> Option<MarketIdentifier> {
+        let event_timestamp = DateTime::parse_from_rfc3339(&self.event_timestamp).ok()?.with_timezone(&Utc);
+        Some(MarketIdentifier {
+            operator: self.operator.clone(),
+            sport: self.sport.clone(),
+            league: self.league.clone(),
+            event: self.event.clone(),
+            market_type: self.market_type.clone(),
+            outcome: self.outcome.clone(),
+            event_timestamp,
+            variant: self.variant.clone(),
+            ladder: self.ladder.clone(),
+        })
+    }
+}
+
+pub fn read_seed(reader: impl Read) -> Result<Vec<SeedEntry>, MigrationError> {
+    let mut entries = Vec::new();
+    for (index, row) in csv::Reader::from_reader(reader).deserialize::<SeedRow>().enumerate() {
+        let row = row?;
+        let identifier = row
+            .identifier()
+            .ok_or_else(|| MigrationError::Csv(format!("row {}: unparseable event_timestamp `{}`", index + 1, row.event_timestamp)))?;
+        entries.push(SeedEntry { identifier, market_uid: MarketUid::parse(&row.market_uid)? });
+    }
+    Ok(entries)
+}
+
+/// Old → new UID of one market.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UidMapping {
+    pub old_uid: MarketUid,
+    pub new_uid: MarketUid,
+}
+
+/// Recomputes each seeded market at `to`, after checking its recorded UID still reproduces at `from`.
+pub fn build_mapping(seed: &[SeedEntry], from: UidVersion, to: UidVersion) -> Result<Vec<UidMapping>, MigrationError> {
+    seed.iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let computed = MarketUid::from_identifier_versioned(&entry.identifier, from)?;
+            if computed != entry.market_uid {
+                return Err(MigrationError::SeedMismatch {
+                    row: index + 1,
+                    version: from,
+                    recorded: entry.market_uid.clone(),
+                    computed,
+                });
+            }
+            let new_uid = MarketUid::from_identifier_versioned(&entry.identifier, to)?;
+            Ok(UidMapping { old_uid: computed, new_uid })
+        })
+        .collect()
+}
+
+pub fn write_mapping(mappings: &[UidMapping], writer: impl Write) -> Result<(), MigrationError> {
+    let mut writer = csv::Writer::from_writer(writer);
+    for mapping in mappings {
+        writer.serialize(mapping)?;
+    }
+    writer.flush().map_err(|err| MigrationError::Csv(err.to_string()))
+}
+
+pub fn read_mapping(reader: impl Read) -> Result<Vec<UidMapping>, MigrationError> {
+    csv::Reader::from_reader(reader)
+        .deserialize()
+        .map(|row| row.map_err(MigrationError::from))
+        .collect()
+}
+
+/// Old UIDs translated to their new version until `window_end`; afterwards UIDs are compared as-is.
+#[derive(Clone, Debug)]
+pub struct UidMigration {
+    targets: HashMap<MarketUid, MarketUid>,
+    window_end: DateTime<Utc>,
+}
+
+impl UidMigration {
+    pub fn new(mappings: impl IntoIterator<Item = UidMapping>, window_end: DateTime<Utc>) -> Result<Self, MigrationError> {
+        let mut targets: HashMap<MarketUid, MarketUid> = HashMap::new();
+        for UidMapping { old_uid, new_uid } in mappings {
+            match targets.get(&old_uid) {
+                Some(existing) if *existing != new_uid => {
+                    return Err(MigrationError::Conflict { uid: old_uid, first: existing.clone(), second: new_uid });
+                }
+                _ => {
+                    targets.insert(old_uid, new_uid);
+                }
+            }
+        }
+        Ok(Self { targets, window_end })
+    }
+
+    pub fn window_end(&self) -> DateTime<Utc> {
+        self.window_end
+    }
+
+    pub fn is_active(&self, at: DateTime<Utc>) -> bool {
+        at < self.window_end
+    }
+
+    /// New UID for a mapped old UID while the window is open, the UID itself otherwise.
+    pub fn translate<'a>(&'a self, uid: &'a MarketUid, at: DateTime<Utc>) -> &'a MarketUid {
+        if self.is_active(at) {
+            self.targets.get(uid).unwrap_or(uid)
+        } else {
+            uid
+        }
+    }
+
+    pub fn same_market(&self, left: &MarketUid, right: &MarketUid, at: DateTime<Utc>) -> bool {
+        self.translate(left, at) == self.translate(right, at)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const SEED: &str = "operator,sport,league,event,market_type,outcome,variant,ladder,event_timestamp,market_uid\n\
+        sx,Soccer,Premier League,Arsenal vs Chelsea,moneyline,home,pre,,2024-08-10T16:30:00Z,muid-v1-b05bf41737061f9a2d1595d7\n";
+
+    #[test]
+    fn mapping_round_trips_and_translates_inside_window() {
+        let seed = read_seed(SEED.as_bytes()).unwrap();
+        let mappings = build_mapping(&seed, UidVersion::V1, UidVersion::V2).unwrap();
+        assert_eq!(mappings[0].new_uid.version(), UidVersion::V2);
+
+        let mut file = Vec::new();
+        write_mapping(&mappings, &mut file).unwrap();
+        assert_eq!(read_mapping(file.as_slice()).unwrap(), mappings);
+
+        let window_end = Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+        let migration = UidMigration::new(mappings.clone(), window_end).unwrap();
+        let (old, new) = (&mappings[0].old_uid, &mappings[0].new_uid);
+        assert!(migration.same_market(old, new, window_end - chrono::Duration::days(1)));
+        assert!(!migration.same_market(old, new, window_end));
+    }
+
+    #[test]
+    fn seed_mismatch_is_reported() {
+        let tampered = SEED.replace("Arsenal vs Chelsea", "Arsenal vs Spurs");
+        let seed = read_seed(tampered.as_bytes()).unwrap();
+        let err = build_mapping(&seed, UidVersion::V1, UidVersion::V2).unwrap_err();
+        assert!(matches!(err, MigrationError::SeedMismatch { row: 1, .. }));
+    }
+}
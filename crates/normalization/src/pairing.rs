@@ -0,0 +1,307 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::dedup::MarketRecord;
+use crate::market_uid::{MarketIdentifier, MarketKey, MarketUid, MarketUidError, UidVersion};
+use crate::taxonomy::{MarketFamily, MarketSpec, Outcome, Side};
+
+/// One side of a cross-venue candidate pair.
+#[derive(Clone, Debug)]
+pub struct PairLeg<T> {
+    pub operator: String,
+    pub outcome: Outcome,
+    pub kickoff: DateTime<Utc>,
+    pub record: MarketRecord<T>,
+}
+
+/// Two records of the same market on different operators with complementary outcomes.
+#[derive(Clone, Debug)]
+pub struct MarketPair<T> {
+    /// Key of the left leg; the right leg's key differs when the kick-offs differ within the tolerance.
+    pub market_key: MarketKey,
+    pub market: MarketSpec,
+    pub left: PairLeg<T>,
+    pub right: PairLeg<T>,
+}
+
+impl<T> MarketPair<T> {
+    pub fn kickoff_gap(&self) -> Duration {
+        (self.left.kickoff - self.right.kickoff).abs()
+    }
+}
+
+/// Record with more than one candidate kick-off on another operator inside the tolerance.
+#[derive(Clone, Debug)]
+pub struct AmbiguousMatch<T> {
+    pub record: MarketRecord<T>,
+    pub kickoff: DateTime<Utc>,
+    pub candidate_kickoffs: Vec<DateTime<Utc>>,
+}
+
+/// Outcome of the pairing pass.
+#[derive(Clone, Debug, Default)]
+pub struct PairingResult<T> {
+    pub pairs: Vec<MarketPair<T>>,
+    pub unmatched: Vec<MarketRecord<T>>,
+    /// Left out of `pairs` and `unmatched`: these need a human or a tighter tolerance.
+    pub ambiguous: Vec<AmbiguousMatch<T>>,
+}
+
+impl<T> PairingResult<T> {
+    pub fn match_ratio(&self, total: usize) -> f64 {
+        let open = self.unmatched.len() + self.ambiguous.len();
+        if total == 0 { 0.0 } else { total.saturating_sub(open) as f64 / total as f64 }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PairingOptions {
+    pub version: UidVersion,
+    /// Largest kick-off difference between two venues still considered the same event.
+    pub kickoff_tolerance: Duration,
+}
+
+impl PairingOptions {
+    pub fn new(version: UidVersion) -> Self {
+        Self { version, kickoff_tolerance: Duration::zero() }
+    }
+
+    pub fn with_kickoff_tolerance(mut self, tolerance: Duration) -> Self {
+        self.kickoff_tolerance = tolerance.abs();
+        self
+    }
+}
+
+impl Default for PairingOptions {
+    fn default() -> Self {
+        Self::new(UidVersion::default())
+    }
+}
+
+/// Group records by operator-agnostic `MarketKey` and emit cross-venue pairs with complementary outcomes.
+///
+/// Keys are recomputed from the identifiers at `version`, so records still carrying v1 UIDs pair with
+/// v2 ones while a UID migration is in progress.
+pub fn pair_markets<T: Clone>(
+    records: impl IntoIterator<Item = (MarketIdentifier, MarketRecord<T>)>,
+    version: UidVersion,
+) -> Result<PairingResult<T>, MarketUidError> {
+    pair_markets_with(records, PairingOptions::new(version))
+}
+
+/// Like `pair_markets`, but kick-offs up to `kickoff_tolerance` apart match. A leg that sees two or more
+/// kick-offs of the same other operator inside the window is reported in `ambiguous` instead of guessed.
+pub fn pair_markets_with<T: Clone>(
+    records: impl IntoIterator<Item = (MarketIdentifier, MarketRecord<T>)>,
+    options: PairingOptions,
+) -> Result<PairingResult<T>, MarketUidError> {
+    pair_markets_with_overrides(records, options, &NoOverrides)
+}
+
+/// Manual decisions consulted before automatic matching, keyed by the legs' `MarketUid`.
+pub trait PairOverrides {
+    /// Counterparts pinned to `uid`; they pair across groups and kick-off tolerance, but only when both legs
+    /// parse in the same market family with complementary outcomes. Other confirmations are ignored.
+    fn confirmed(&self, uid: &MarketUid) -> &[MarketUid];
+    /// Pairs automatic matching must never emit.
+    fn is_rejected(&self, left: &MarketUid, right: &MarketUid) -> bool;
+}
+
+/// No manual decisions: automatic matching only.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoOverrides;
+
+impl PairOverrides for NoOverrides {
+    fn confirmed(&self, _uid: &MarketUid) -> &[MarketUid] {
+        &[]
+    }
+
+    fn is_rejected(&self, _left: &MarketUid, _right: &MarketUid) -> bool {
+        false
+    }
+}
+
+/// Like `pair_markets_with`, with `overrides` taking precedence: confirmed legs are paired first and
+/// left out of automatic matching, rejected pairs are never emitted.
+pub fn pair_markets_with_overrides<T: Clone>(
+    records: impl IntoIterator<Item = (MarketIdentifier, MarketRecord<T>)>,
+    options: PairingOptions,
+    overrides: &impl PairOverrides,
+) -> Result<PairingResult<T>, MarketUidError> {
+    let mut order = Vec::new();
+    let mut groups: HashMap<String, Vec<(MarketIdentifier, MarketKey, MarketRecord<T>)>> = HashMap::new();
+    for (identifier, record) in records {
+        let key = MarketKey::from_identifier(&identifier, options.version)?;
+        let group = group_key(&identifier, options.version)?;
+        if identifier.outcome.trim().is_empty() {
+            return Err(MarketUidError::MissingField("outcome"));
+        }
+        groups
+            .entry(group.clone())
+            .or_insert_with(|| {
+                order.push(group);
+                Vec::new()
+            })
+            .push((identifier, key, record));
+    }
+
+    let mut result = PairingResult { pairs: Vec::new(), unmatched: Vec::new(), ambiguous: Vec::new() };
+    let mut typed = Vec::with_capacity(order.len());
+    for group in order {
+        let entries = groups.remove(&group).unwrap_or_default();
+        // Records outside the taxonomy cannot be proven complementary and stay unmatched.
+        let Some(market) = group_market(&entries) else {
+            result.unmatched.extend(entries.into_iter().map(|(_, _, record)| record));
+            continue;
+        };
+        let mut legs = Vec::with_capacity(entries.len());
+        for (identifier, key, record) in entries {
+            match Outcome::parse(market.family, &identifier.outcome) {
+                Ok(outcome) => legs.push((
+                    key,
+                    PairLeg { operator: identifier.canonical_operator()?, outcome, kickoff: identifier.kickoff_minute(), record },
+                )),
+                Err(_) => result.unmatched.push(record),
+            }
+        }
+        typed.push((market, legs));
+    }
+
+    let pinned = pin_confirmed(&typed, overrides, &mut result);
+    for (group, (market, legs)) in typed.into_iter().enumerate() {
+        let legs: Vec<_> =
+            legs.into_iter().enumerate().filter(|(leg, _)| !pinned.contains(&(group, *leg))).map(|(_, leg)| leg).collect();
+        let (candidates, direct): (Vec<_>, Vec<bool>) =
+            legs.iter().map(|(_, leg)| candidate_kickoffs(leg, &legs, options.kickoff_tolerance)).unzip();
+        // Counterparts of an ambiguous leg share its ambiguity rather than ending up unmatched.
+        let ambiguous: Vec<bool> = legs
+            .iter()
+            .map(|(_, leg)| {
+                legs.iter().zip(&direct).any(|((_, other), &flagged)| {
+                    let same_listing = other.operator == leg.operator && other.kickoff == leg.kickoff;
+                    flagged && (same_listing || cross_venue_within(leg, other, options.kickoff_tolerance))
+                })
+            })
+            .collect();
+        let mut matched = vec![false; legs.len()];
+        for i in 0..legs.len() {
+            for j in (i + 1)..legs.len() {
+                let ((left_key, left), (_, right)) = (&legs[i], &legs[j]);
+                if ambiguous[i] || ambiguous[j] || !cross_venue_within(left, right, options.kickoff_tolerance) {
+                    continue;
+                }
+                if overrides.is_rejected(&left.record.key.uid, &right.record.key.uid) {
+                    continue;
+                }
+                let outcomes: BTreeSet<&Outcome> = legs
+                    .iter()
+                    .filter(|(_, leg)| leg.kickoff == left.kickoff || leg.kickoff == right.kickoff)
+                    .map(|(_, leg)| &leg.outcome)
+                    .collect();
+                if are_complementary(&left.outcome, &right.outcome, market.family, &outcomes) {
+                    matched[i] = true;
+                    matched[j] = true;
+                    result.pairs.push(MarketPair {
+                        market_key: left_key.clone(),
+                        market,
+                        left: left.clone(),
+                        right: right.clone(),
+                    });
+                }
+            }
+        }
+        for ((((_, leg), hit), ambiguous), candidate_kickoffs) in legs.into_iter().zip(matched).zip(ambiguous).zip(candidates) {
+            if ambiguous {
+                result.ambiguous.push(AmbiguousMatch { kickoff: leg.kickoff, record: leg.record, candidate_kickoffs });
+            } else if !hit {
+                result.unmatched.push(leg.record);
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Legs of one untimed group with the market they were typed against.
+type TypedGroup<T> = (MarketSpec, Vec<(MarketKey, PairLeg<T>)>);
+
+/// Emits the confirmed pairs present in `typed` and returns the (group, leg) positions they consumed.
+/// A confirmation between non-complementary outcomes is not pinned; its legs go through automatic matching.
+fn pin_confirmed<T: Clone>(
+    typed: &[TypedGroup<T>],
+    overrides: &impl PairOverrides,
+    result: &mut PairingResult<T>,
+) -> HashSet<(usize, usize)> {
+    let positions: HashMap<&MarketUid, (usize, usize)> = typed
+        .iter()
+        .enumerate()
+        .flat_map(|(group, (_, legs))| legs.iter().enumerate().map(move |(leg, (_, pair_leg))| (&pair_leg.record.key.uid, (group, leg))))
+        .collect();
+    let mut pinned = HashSet::new();
+    for (group, (market, legs)) in typed.iter().enumerate() {
+        for (leg, (key, left)) in legs.iter().enumerate() {
+            for counterpart in overrides.confirmed(&left.record.key.uid) {
+                let Some(&(other_group, other_leg)) = positions.get(counterpart) else {
+                    continue;
+                };
+                let (other_market, other_legs) = &typed[other_group];
+                let right = &other_legs[other_leg].1;
+                let fresh = !pinned.contains(&(group, leg)) && !pinned.contains(&(other_group, other_leg));
+                let complementary = other_market.family == market.family && left.outcome.is_complement_of(&right.outcome, market.family);
+                if fresh && complementary && right.operator != left.operator {
+                    pinned.insert((group, leg));
+                    pinned.insert((other_group, other_leg));
+                    result.pairs.push(MarketPair { market_key: key.clone(), market: *market, left: left.clone(), right: right.clone() });
+                }
+            }
+        }
+    }
+    pinned
+}
+
+/// Untimed fingerprint on the taxonomy's canonical market type, so synonyms (`totals`, `total_points`)
+/// share a group; market types outside the taxonomy keep their raw form.
+fn group_key(identifier: &MarketIdentifier, version: UidVersion) -> Result<String, MarketUidError> {
+    match MarketSpec::parse(&identifier.sport, &identifier.market_type) {
+        Ok(spec) => MarketIdentifier { market_type: spec.canonical(), ..identifier.clone() }.untimed_market_fingerprint(version),
+        Err(_) => identifier.untimed_market_fingerprint(version),
+    }
+}
+
+/// Typed market of a group; a winner market listing a draw is settled three-way whatever the sport.
+fn group_market<T>(entries: &[(MarketIdentifier, MarketKey, MarketRecord<T>)]) -> Option<MarketSpec> {
+    let (identifier, _, _) = entries.first()?;
+    let mut market = MarketSpec::parse(&identifier.sport, &identifier.market_type).ok()?;
+    let lists_draw = entries.iter().any(|(identifier, _, _)| {
+        Outcome::parse(MarketFamily::Moneyline3Way, &identifier.outcome)
+            .is_ok_and(|outcome| !matches!(outcome.side, Side::Home | Side::Away))
+    });
+    if market.family == MarketFamily::Moneyline2Way && lists_draw {
+        market.family = MarketFamily::Moneyline3Way;
+    }
+    Some(market)
+}
+
+/// Kick-offs of other operators' legs within `tolerance` of `leg`, and whether one operator offers several.
+fn candidate_kickoffs<T>(leg: &PairLeg<T>, legs: &[(MarketKey, PairLeg<T>)], tolerance: Duration) -> (Vec<DateTime<Utc>>, bool) {
+    let mut per_operator: HashMap<&str, BTreeSet<DateTime<Utc>>> = HashMap::new();
+    for (_, other) in legs {
+        if cross_venue_within(leg, other, tolerance) {
+            per_operator.entry(other.operator.as_str()).or_default().insert(other.kickoff);
+        }
+    }
+    let ambiguous = per_operator.values().any(|kickoffs| kickoffs.len() > 1);
+    let kickoffs: BTreeSet<DateTime<Utc>> = per_operator.into_values().flatten().collect();
+    (kickoffs.into_iter().collect(), ambiguous)
+}
+
+fn cross_venue_within<T>(leg: &PairLeg<T>, other: &PairLeg<T>, tolerance: Duration) -> bool {
+    other.operator != leg.operator && (other.kickoff - leg.kickoff).abs() <= tolerance
+}
+
+/// Outcomes that cover the whole event when backed together. Named participants only complement each
+/// other when they are the market's only two outcomes.
+fn are_complementary(a: &Outcome, b: &Outcome, family: MarketFamily, outcomes: &BTreeSet<&Outcome>) -> bool {
+    let named = matches!(a.side, Side::Participant(_)) || matches!(b.side, Side::Participant(_));
+    a.is_complement_of(b, family) && (!named || outcomes.len() == 2)
+}
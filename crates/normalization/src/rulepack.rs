@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::market_uid::{AsciiStrategy, FingerprintStrategy, MarketIdentifier};
+use crate::taxonomy::Period;
+
+pub const RULEPACK_SCHEMA_VERSION: u32 = 1;
+/// Selector value matching every market type of a sport.
+const WILDCARD: &str = "*";
+const HANDICAP_MARKETS: [&str; 3] = ["spread", "handicap", "asian_handicap"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Overtime {
+    Included,
+    Excluded,
+}
+
+/// What happens to a bet when the event does not start as scheduled.
+/// Written `void`, `stands` or `{ void_after_hours: 48 }` in YAML and JSON alike.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "PostponementRepr", into = "PostponementRepr")]
+pub enum Postponement {
+    /// Void as soon as the event is postponed.
+    Void,
+    /// Stands if the event is played within the window, void otherwise.
+    VoidAfterHours(u32),
+    /// Bet stands whenever the event is eventually played.
+    Stands,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+enum PostponementRepr {
+    Keyword(String),
+    Window { void_after_hours: u32 },
+}
+
+impl TryFrom<PostponementRepr> for Postponement {
+    type Error = String;
+
+    fn try_from(repr: PostponementRepr) -> Result<Self, Self::Error> {
+        match repr {
+            PostponementRepr::Keyword(keyword) => match keyword.as_str() {
+                "void" => Ok(Postponement::Void),
+                "stands" => Ok(Postponement::Stands),
+                other => Err(format!("unknown postponement rule `{other}`")),
+            },
+            PostponementRepr::Window { void_after_hours } => Ok(Postponement::VoidAfterHours(void_after_hours)),
+        }
+    }
+}
+
+impl From<Postponement> for PostponementRepr {
+    fn from(rule: Postponement) -> Self {
+        match rule {
+            Postponement::Void => PostponementRepr::Keyword("void".into()),
+            Postponement::Stands => PostponementRepr::Keyword("stands".into()),
+            Postponement::VoidAfterHours(void_after_hours) => PostponementRepr::Window { void_after_hours },
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HandicapSettlement {
+    /// Quarter/half lines, push refunds.
+    Asian,
+    /// Three-way handicap with a handicap draw outcome.
+    European,
+}
+
+/// Settlement semantics of one operator's market.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SettlementRules {
+    pub overtime: Overtime,
+    pub period: Period,
+    pub postponement: Postponement,
+    #[serde(default)]
+    pub handicap_settlement: Option<HandicapSettlement>,
+}
+
+/// One rulepack entry; `market_type: "*"` is the sport-wide default.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleDefinition {
+    operator: String,
+    sport: String,
+    market_type: String,
+    overtime: Overtime,
+    period: Period,
+    postponement: Postponement,
+    #[serde(default)]
+    handicap_settlement: Option<HandicapSettlement>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulepackFile {
+    version: u32,
+    #[serde(default)]
+    rules: Vec<RuleDefinition>,
+}
+
+#[derive(Debug, Error)]
+pub enum RulepackError {
+    #[error("failed to read rulepack `{path}`: {source}")]
+    Io { path: String, source: std::io::Error },
+    #[error("invalid rulepack: {0}")]
+    Parse(String),
+    #[error("rulepack failed validation: {}", .0.join("; "))]
+    Invalid(Vec<String>),
+}
+
+/// A rule on which two paired legs would settle differently.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum RuleConflict {
+    Overtime { left: Overtime, right: Overtime },
+    Period { left: Period, right: Period },
+    Postponement { left: Postponement, right: Postponement },
+    HandicapSettlement { left: Option<HandicapSettlement>, right: Option<HandicapSettlement> },
+    /// No rule is known for a leg: its settlement cannot be assumed compatible.
+    MissingRules { operator: String, sport: String, market_type: String },
+}
+
+impl fmt::Display for RuleConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleConflict::Overtime { left, right } => write!(f, "overtime {left:?} vs {right:?}"),
+            RuleConflict::Period { left, right } => write!(f, "period {left} vs {right}"),
+            RuleConflict::Postponement { left, right } => write!(f, "postponement {left:?} vs {right:?}"),
+            RuleConflict::HandicapSettlement { left, right } => write!(f, "handicap settlement {left:?} vs {right:?}"),
+            RuleConflict::MissingRules { operator, sport, market_type } => {
+                write!(f, "no settlement rules for {operator}/{sport}/{market_type}")
+            }
+        }
+    }
+}
+
+/// Ok when both legs settle identically, otherwise every diverging rule.
+pub fn compatible(a: &SettlementRules, b: &SettlementRules) -> Result<(), Vec<RuleConflict>> {
+    let mut conflicts = Vec::new();
+    if a.overtime != b.overtime {
+        conflicts.push(RuleConflict::Overtime { left: a.overtime, right: b.overtime });
+    }
+    if a.period != b.period {
+        conflicts.push(RuleConflict::Period { left: a.period, right: b.period });
+    }
+    if a.postponement != b.postponement {
+        conflicts.push(RuleConflict::Postponement { left: a.postponement, right: b.postponement });
+    }
+    if a.handicap_settlement != b.handicap_settlement {
+        conflicts.push(RuleConflict::HandicapSettlement { left: a.handicap_settlement, right: b.handicap_settlement });
+    }
+    if conflicts.is_empty() { Ok(()) } else { Err(conflicts) }
+}
+
+/// Settlement rules per (operator, sport, market type), with sport-wide `*` defaults.
+#[derive(Clone, Debug, Default)]
+pub struct Rulepack {
+    rules: HashMap<(String, String, String), SettlementRules>,
+}
+
+impl Rulepack {
+    pub fn from_yaml_str(input: &str) -> Result<Self, RulepackError> {
+        let file: RulepackFile = serde_yaml::from_str(input).map_err(|err| RulepackError::Parse(err.to_string()))?;
+        Self::from_file(file)
+    }
+
+    pub fn from_json_str(input: &str) -> Result<Self, RulepackError> {
+        let file: RulepackFile = serde_json::from_str(input).map_err(|err| RulepackError::Parse(err.to_string()))?;
+        Self::from_file(file)
+    }
+
+    /// Loads a `.json` rulepack, or YAML for any other extension.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RulepackError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .map_err(|source| RulepackError::Io { path: path.display().to_string(), source })?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Self::from_json_str(&contents),
+            _ => Self::from_yaml_str(&contents),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Exact market type first, then the sport-wide default.
+    pub fn lookup(&self, operator: &str, sport: &str, market_type: &str) -> Option<&SettlementRules> {
+        let (operator, sport) = (selector(operator), selector(sport));
+        self.rules
+            .get(&(operator.clone(), sport.clone(), selector(market_type)))
+            .or_else(|| self.rules.get(&(operator, sport, WILDCARD.to_string())))
+    }
+
+    /// Compares the rules of two paired legs; an unknown leg is reported as `MissingRules`.
+    pub fn compatible_markets(&self, a: &MarketIdentifier, b: &MarketIdentifier) -> Result<(), Vec<RuleConflict>> {
+        match (self.rules_for(a), self.rules_for(b)) {
+            (Ok(left), Ok(right)) => compatible(left, right),
+            (left, right) => Err([left.err(), right.err()].into_iter().flatten().collect()),
+        }
+    }
+
+    fn rules_for(&self, identifier: &MarketIdentifier) -> Result<&SettlementRules, RuleConflict> {
+        self.lookup(&identifier.operator, &identifier.sport, &identifier.market_type).ok_or_else(|| {
+            RuleConflict::MissingRules {
+                operator: selector(&identifier.operator),
+                sport: selector(&identifier.sport),
+                market_type: selector(&identifier.market_type),
+            }
+        })
+    }
+
+    fn from_file(file: RulepackFile) -> Result<Self, RulepackError> {
+        let mut violations = Vec::new();
+        if file.version != RULEPACK_SCHEMA_VERSION {
+            violations.push(format!("unsupported version {} (expected {RULEPACK_SCHEMA_VERSION})", file.version));
+        }
+        let mut rules = HashMap::new();
+        for (index, definition) in file.rules.into_iter().enumerate() {
+            let at = format!("rules[{index}]");
+            for (field, value) in [("operator", &definition.operator), ("sport", &definition.sport), ("market_type", &definition.market_type)] {
+                if value.trim().is_empty() {
+                    violations.push(format!("{at}.{field} is empty"));
+                }
+            }
+            let market_type = selector(&definition.market_type);
+            let handicap_market = HANDICAP_MARKETS.contains(&market_type.as_str());
+            match (handicap_market, definition.handicap_settlement) {
+                (true, None) => violations.push(format!("{at}.handicap_settlement is required for `{market_type}`")),
+                (false, Some(_)) if market_type != WILDCARD => {
+                    violations.push(format!("{at}.handicap_settlement only applies to handicap markets"))
+                }
+                _ => {}
+            }
+            if definition.postponement == Postponement::VoidAfterHours(0) {
+                violations.push(format!("{at}.postponement.void_after_hours must be positive"));
+            }
+            let key = (selector(&definition.operator), selector(&definition.sport), market_type);
+            let settlement = SettlementRules {
+                overtime: definition.overtime,
+                period: definition.period,
+                postponement: definition.postponement,
+                handicap_settlement: definition.handicap_settlement,
+            };
+            if rules.insert(key.clone(), settlement).is_some() {
+                violations.push(format!("{at} duplicates {}/{}/{}", key.0, key.1, key.2));
+            }
+        }
+        if violations.is_empty() { Ok(Self { rules }) } else { Err(RulepackError::Invalid(violations)) }
+    }
+}
+
+fn selector(value: &str) -> String {
+    let value = value.trim();
+    if value == WILDCARD { value.to_string() } else { AsciiStrategy.canonicalize(value) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(overtime: Overtime, postponement: Postponement) -> SettlementRules {
+        SettlementRules { overtime, period: Period::FullTime, postponement, handicap_settlement: None }
+    }
+
+    #[test]
+    fn compatible_lists_every_diverging_rule() {
+        let sx = rules(Overtime::Included, Postponement::VoidAfterHours(48));
+        assert_eq!(compatible(&sx, &sx.clone()), Ok(()));
+
+        let azuro = rules(Overtime::Excluded, Postponement::Void);
+        let conflicts = compatible(&sx, &azuro).unwrap_err();
+        assert_eq!(
+            conflicts,
+            vec![
+                RuleConflict::Overtime { left: Overtime::Included, right: Overtime::Excluded },
+                RuleConflict::Postponement { left: Postponement::VoidAfterHours(48), right: Postponement::Void },
+            ]
+        );
+        assert_eq!(conflicts[0].to_string(), "overtime Included vs Excluded");
+    }
+
+    #[test]
+    fn validation_collects_all_violations() {
+        let yaml = r#"
+version: 1
+rules:
+  - { operator: sx, sport: soccer, market_type: spread, overtime: excluded, period: full_game, postponement: void }
+  - { operator: sx, sport: soccer, market_type: total, overtime: excluded, period: full_game, postponement: void, handicap_settlement: asian }
+  - { operator: SX, sport: Soccer, market_type: Total, overtime: excluded, period: full_game, postponement: { void_after_hours: 0 } }
+"#;
+        let RulepackError::Invalid(violations) = Rulepack::from_yaml_str(yaml).unwrap_err() else {
+            panic!("expected validation failure");
+        };
+        assert_eq!(violations.len(), 4, "{violations:?}");
+        assert!(violations[0].contains("handicap_settlement is required"));
+        assert!(violations[3].contains("duplicates sx/soccer/total"));
+
+        let unknown_field = "version: 1\nrules:\n  - { operator: sx, sport: soccer, market_type: total, overtime: excluded, period: full_game, postponement: void, ot: true }\n";
+        assert!(matches!(Rulepack::from_yaml_str(unknown_field), Err(RulepackError::Parse(_))));
+    }
+}
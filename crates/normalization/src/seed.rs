@@ -0,0 +1,282 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::market_uid::{MarketKey, MarketUid, UidVersion};
+use crate::migration::SeedRow;
+use crate::taxonomy::{MarketFamily, MarketSpec, Outcome, Side};
+
+const ONE_X_TWO: [Side; 3] = [Side::Home, Side::Draw, Side::Away];
+const DOUBLE_CHANCE: [Side; 3] = [Side::HomeOrDraw, Side::DrawOrAway, Side::HomeOrAway];
+
+/// Operator + `MarketKey`: the outcomes one venue lists for one market.
+type Listing = (String, MarketKey);
+
+#[derive(Debug, Error)]
+pub enum SeedError {
+    #[error("invalid csv: {0}")]
+    Csv(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+impl From<csv::Error> for SeedError {
+    fn from(err: csv::Error) -> Self {
+        SeedError::Csv(err.to_string())
+    }
+}
+
+/// Problem found in one seed row; `row` is 1-based, header excluded.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum SeedIssue {
+    UnparseableTimestamp { row: usize, value: String },
+    InvalidUid { row: usize, value: String, error: String },
+    Mismatch { row: usize, recorded: String, computed: MarketUid },
+    Duplicate { row: usize, first_row: usize, market_uid: MarketUid },
+    MissingComplement { row: usize, outcome: String },
+    /// Identifier rejected by the fingerprint (empty required field).
+    InvalidIdentifier { row: usize, error: String },
+    /// Market type or outcome outside the taxonomy, so complements cannot be checked.
+    Untyped { row: usize, error: String },
+}
+
+impl SeedIssue {
+    pub fn row(&self) -> usize {
+        match self {
+            SeedIssue::UnparseableTimestamp { row, .. }
+            | SeedIssue::InvalidUid { row, .. }
+            | SeedIssue::Mismatch { row, .. }
+            | SeedIssue::Duplicate { row, .. }
+            | SeedIssue::MissingComplement { row, .. }
+            | SeedIssue::InvalidIdentifier { row, .. }
+            | SeedIssue::Untyped { row, .. } => *row,
+        }
+    }
+}
+
+impl fmt::Display for SeedIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedIssue::UnparseableTimestamp { row, value } => write!(f, "row {row}: unparseable event_timestamp `{value}`"),
+            SeedIssue::InvalidUid { row, value, error } => write!(f, "row {row}: invalid market_uid `{value}`: {error}"),
+            SeedIssue::Mismatch { row, recorded, computed } => write!(f, "row {row}: recorded `{recorded}` but computed `{computed}`"),
+            SeedIssue::Duplicate { row, first_row, market_uid } => write!(f, "row {row}: `{market_uid}` duplicates row {first_row}"),
+            SeedIssue::MissingComplement { row, outcome } => write!(f, "row {row}: no complementary outcome for `{outcome}`"),
+            SeedIssue::InvalidIdentifier { row, error } => write!(f, "row {row}: invalid identifier: {error}"),
+            SeedIssue::Untyped { row, error } => write!(f, "row {row}: outside the market taxonomy: {error}"),
+        }
+    }
+}
+
+/// Result of `SeedCatalogue::validate`, serialisable for CI gates.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct SeedReport {
+    pub version: UidVersion,
+    pub rows: usize,
+    pub issues: Vec<SeedIssue>,
+}
+
+impl SeedReport {
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// `data/market_uid_seed.csv` loaded for validation or regeneration.
+#[derive(Clone, Debug, Default)]
+pub struct SeedCatalogue {
+    rows: Vec<SeedRow>,
+    /// The committed seed uses CRLF; rewrites keep whatever terminator was read.
+    crlf: bool,
+}
+
+impl SeedCatalogue {
+    pub fn read(mut reader: impl Read) -> Result<Self, SeedError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        let crlf = bytes.windows(2).any(|pair| pair == b"\r\n");
+        let rows = csv::Reader::from_reader(bytes.as_slice()).deserialize().collect::<Result<_, _>>()?;
+        Ok(Self { rows, crlf })
+    }
+
+    pub fn write(&self, writer: impl Write) -> Result<(), SeedError> {
+        let terminator = if self.crlf { csv::Terminator::CRLF } else { csv::Terminator::Any(b'\n') };
+        let mut writer = csv::WriterBuilder::new().terminator(terminator).from_writer(writer);
+        for row in &self.rows {
+            writer.serialize(row)?;
+        }
+        Ok(writer.flush()?)
+    }
+
+    /// Writes to a sibling temp file and renames it over `path`, so an interrupted rewrite never
+    /// leaves a truncated seed behind.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), SeedError> {
+        let path = path.as_ref();
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(".tmp");
+        let temp = path.with_file_name(name);
+        let written = fs::File::create(&temp)
+            .map_err(SeedError::from)
+            .and_then(|mut file| {
+                self.write(&mut file)?;
+                Ok(file.sync_all()?)
+            })
+            .and_then(|()| Ok(fs::rename(&temp, path)?));
+        if written.is_err() {
+            let _ = fs::remove_file(&temp);
+        }
+        written
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Recomputes every UID at `version` and checks duplicates and complementary outcomes.
+    pub fn validate(&self, version: UidVersion) -> SeedReport {
+        let mut issues = Vec::new();
+        let mut first_rows: HashMap<MarketUid, usize> = HashMap::new();
+        let mut listings: HashMap<Listing, Vec<(usize, MarketFamily, Outcome)>> = HashMap::new();
+
+        for (index, raw) in self.rows.iter().enumerate() {
+            let row = index + 1;
+            if let Err(err) = MarketUid::parse(&raw.market_uid) {
+                issues.push(SeedIssue::InvalidUid { row, value: raw.market_uid.clone(), error: err.to_string() });
+            }
+            let Some(identifier) = raw.identifier() else {
+                issues.push(SeedIssue::UnparseableTimestamp { row, value: raw.event_timestamp.clone() });
+                continue;
+            };
+            let computed = match MarketUid::from_identifier_versioned(&identifier, version) {
+                Ok(uid) => uid,
+                Err(err) => {
+                    issues.push(SeedIssue::InvalidIdentifier { row, error: err.to_string() });
+                    continue;
+                }
+            };
+            if computed.as_str() != raw.market_uid {
+                issues.push(SeedIssue::Mismatch { row, recorded: raw.market_uid.clone(), computed: computed.clone() });
+            }
+            if let Some(&first_row) = first_rows.get(&computed) {
+                issues.push(SeedIssue::Duplicate { row, first_row, market_uid: computed });
+                continue;
+            }
+            first_rows.insert(computed, row);
+
+            let typed = MarketSpec::parse(&identifier.sport, &identifier.market_type)
+                .and_then(|spec| Outcome::parse(spec.family, &identifier.outcome).map(|outcome| (spec.family, outcome)));
+            let listing = identifier
+                .canonical_operator()
+                .and_then(|operator| MarketKey::from_identifier(&identifier, version).map(|key| (operator, key)));
+            match (typed, listing) {
+                (Ok((family, outcome)), Ok(listing)) => listings.entry(listing).or_default().push((row, family, outcome)),
+                (Err(err), _) => issues.push(SeedIssue::Untyped { row, error: err.to_string() }),
+                (_, Err(err)) => issues.push(SeedIssue::InvalidIdentifier { row, error: err.to_string() }),
+            }
+        }
+
+        for legs in listings.values() {
+            let outcomes: BTreeSet<&Outcome> = legs.iter().map(|(_, _, outcome)| outcome).collect();
+            for (row, family, outcome) in legs {
+                if !is_covered(outcome, *family, &outcomes) {
+                    issues.push(SeedIssue::MissingComplement { row: *row, outcome: outcome.canonical() });
+                }
+            }
+        }
+        issues.sort_by_key(SeedIssue::row);
+        SeedReport { version, rows: self.rows.len(), issues }
+    }
+
+    /// Rewrites `market_uid` of every row whose identifier parses; returns how many changed.
+    pub fn regenerate(&mut self, version: UidVersion) -> usize {
+        let mut changed = 0;
+        for raw in &mut self.rows {
+            let Some(uid) = raw.identifier().and_then(|identifier| MarketUid::from_identifier_versioned(&identifier, version).ok()) else {
+                continue;
+            };
+            if uid.as_str() != raw.market_uid {
+                raw.market_uid = uid.to_string();
+                changed += 1;
+            }
+        }
+        changed
+    }
+}
+
+/// A listing covers an outcome when its complement is listed, or when the full 1X2 / double-chance
+/// triple is listed (the three legs cover each other jointly).
+fn is_covered(outcome: &Outcome, family: MarketFamily, outcomes: &BTreeSet<&Outcome>) -> bool {
+    let named = matches!(outcome.side, Side::Participant(_));
+    let direct = outcomes.iter().any(|other| outcome.is_complement_of(other, family));
+    if direct && (!named || outcomes.len() == 2) {
+        return true;
+    }
+    let listed = |sides: &[Side; 3]| sides.iter().all(|side| outcomes.iter().any(|other| other.side == *side && other.line.is_none()));
+    (ONE_X_TWO.contains(&outcome.side) && listed(&ONE_X_TWO)) || (DOUBLE_CHANCE.contains(&outcome.side) && listed(&DOUBLE_CHANCE))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEED: &str = "operator,sport,league,event,market_type,outcome,variant,ladder,event_timestamp,market_uid\n\
+        sx,Soccer,Premier League,Arsenal vs Chelsea,moneyline,home,pre,,2024-08-10T16:30:00Z,muid-v1-b05bf41737061f9a2d1595d7\n\
+        sx,Soccer,Premier League,Arsenal vs Chelsea,moneyline,away,pre,,2024-08-10T16:30:00Z,muid-v1-35faaad0bc9b621891a4dc7e\n\
+        sx,Soccer,Premier League,Arsenal vs Chelsea,moneyline,draw,pre,,2024-08-10T16:30:00Z,muid-v1-026cf097571f584ea96463a6\n";
+
+    #[test]
+    fn complete_listing_is_clean_and_round_trips() {
+        let catalogue = SeedCatalogue::read(SEED.as_bytes()).unwrap();
+        assert!(catalogue.validate(UidVersion::V1).is_clean());
+        let mut written = Vec::new();
+        catalogue.write(&mut written).unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), SEED);
+
+        let crlf = SEED.replace('\n', "\r\n");
+        let mut written = Vec::new();
+        SeedCatalogue::read(crlf.as_bytes()).unwrap().write(&mut written).unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), crlf);
+    }
+
+    #[test]
+    fn reports_each_kind_of_problem() {
+        let broken = SEED
+            .replacen("muid-v1-b05bf41737061f9a2d1595d7", "muid-v1-000000000000000000000000", 1)
+            .replace("away,pre,,2024-08-10T16:30:00Z,muid-v1-35faaad0bc9b621891a4dc7e", "home,pre,,2024-08-10T16:30:00Z,muid-v1-b05bf41737061f9a2d1595d7")
+            .replace("2024-08-10T16:30:00Z,muid-v1-026cf", "10/08/2024 16:30,muid-v1-026cf");
+        let report = SeedCatalogue::read(broken.as_bytes()).unwrap().validate(UidVersion::V1);
+        let kinds: Vec<_> = report.issues.iter().map(|issue| serde_json::to_value(issue).unwrap()["kind"].clone()).collect();
+        assert_eq!(kinds, ["mismatch", "missing_complement", "duplicate", "unparseable_timestamp"]);
+    }
+
+    #[test]
+    fn regenerate_fixes_mismatches() {
+        let stale = SEED.replace("muid-v1-b05bf41737061f9a2d1595d7", "muid-v1-000000000000000000000000");
+        let mut catalogue = SeedCatalogue::read(stale.as_bytes()).unwrap();
+        assert_eq!(catalogue.regenerate(UidVersion::V1), 1);
+        assert!(catalogue.validate(UidVersion::V1).is_clean());
+    }
+
+    #[test]
+    fn save_replaces_the_file_without_leaving_a_temp_behind() {
+        let dir = std::env::temp_dir().join(format!("otter-seed-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("market_uid_seed.csv");
+        fs::write(&path, "stale").unwrap();
+
+        SeedCatalogue::read(SEED.as_bytes()).unwrap().save(&path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), SEED);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
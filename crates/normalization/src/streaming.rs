@@ -0,0 +1,369 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::dedup::{Choice, DedupKey, DedupPolicy, Dropped, FirstSeen, MarketRecord};
+
+/// Time source of the streaming deduplicator; swap in `ManualClock` for deterministic tests and replays.
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Clock that only moves when told to.
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl ManualClock {
+    pub fn new(start: DateTime<Utc>) -> Self {
+        Self { now: Mutex::new(start) }
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = now;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        *now += by;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now(&self) -> DateTime<Utc> {
+        (**self).now()
+    }
+}
+
+/// Bounds on the keys held by `StreamingDeduplicator`; at least one should be set for long-running feeds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StreamingConfig {
+    /// Keys untouched for this long are forgotten. A touch is dated by the record's `observed_at`
+    /// (capped at the clock), or by the clock when the record has none.
+    pub ttl: Option<Duration>,
+    /// Least recently touched keys are evicted beyond this many.
+    pub capacity: Option<usize>,
+}
+
+impl StreamingConfig {
+    pub fn unbounded() -> Self {
+        Self { ttl: None, capacity: None }
+    }
+
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self { ttl: Some(Duration::minutes(10)), capacity: Some(100_000) }
+    }
+}
+
+/// What happened to one record pushed through the stream.
+#[derive(Clone, Debug)]
+pub enum StreamDecision<T> {
+    /// First record for its key inside the window; forward it.
+    Retained(MarketRecord<T>),
+    /// The policy preferred this record over the held one, which is returned as dropped.
+    Replaced { record: MarketRecord<T>, dropped: Dropped<T> },
+    /// The held record stays; the incoming one is dropped.
+    Duplicate(Dropped<T>),
+}
+
+impl<T> StreamDecision<T> {
+    pub fn is_duplicate(&self) -> bool {
+        !matches!(self, StreamDecision::Retained(_))
+    }
+}
+
+/// Running counters since the deduplicator was created.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StreamStats {
+    pub seen: u64,
+    pub retained: u64,
+    pub replaced: u64,
+    pub duplicates: u64,
+    pub expired: u64,
+    pub evicted: u64,
+}
+
+impl StreamStats {
+    /// Share of pushed records that collided with a held key (replacements included).
+    pub fn duplicate_ratio(&self) -> f64 {
+        if self.seen == 0 { 0.0 } else { (self.replaced + self.duplicates) as f64 / self.seen as f64 }
+    }
+}
+
+#[derive(Debug)]
+struct Held<T> {
+    record: MarketRecord<T>,
+    touched_at: DateTime<Utc>,
+    sequence: u64,
+}
+
+/// Stateful dedup for live quote feeds: keys live for `ttl` after their last touch and at most
+/// `capacity` keys are held, least recently touched evicted first. A late or replayed quote older
+/// than the key's last touch does not extend its life.
+#[derive(Debug)]
+pub struct StreamingDeduplicator<T, P = FirstSeen, C = SystemClock> {
+    config: StreamingConfig,
+    policy: P,
+    clock: C,
+    held: HashMap<DedupKey, Held<T>>,
+    /// Touch order: (touched_at, sequence) → key, oldest first.
+    recency: BTreeMap<(DateTime<Utc>, u64), DedupKey>,
+    next_sequence: u64,
+    stats: StreamStats,
+}
+
+impl<T> StreamingDeduplicator<T> {
+    pub fn new(config: StreamingConfig) -> Self {
+        Self {
+            config,
+            policy: FirstSeen,
+            clock: SystemClock,
+            held: HashMap::new(),
+            recency: BTreeMap::new(),
+            next_sequence: 0,
+            stats: StreamStats::default(),
+        }
+    }
+}
+
+impl<T, P, C> StreamingDeduplicator<T, P, C> {
+    pub fn with_policy<Q: DedupPolicy<T>>(self, policy: Q) -> StreamingDeduplicator<T, Q, C> {
+        StreamingDeduplicator {
+            config: self.config,
+            policy,
+            clock: self.clock,
+            held: self.held,
+            recency: self.recency,
+            next_sequence: self.next_sequence,
+            stats: self.stats,
+        }
+    }
+
+    pub fn with_clock<D: Clock>(self, clock: D) -> StreamingDeduplicator<T, P, D> {
+        StreamingDeduplicator {
+            config: self.config,
+            policy: self.policy,
+            clock,
+            held: self.held,
+            recency: self.recency,
+            next_sequence: self.next_sequence,
+            stats: self.stats,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.held.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.held.is_empty()
+    }
+
+    pub fn stats(&self) -> StreamStats {
+        self.stats
+    }
+
+    pub fn duplicate_ratio(&self) -> f64 {
+        self.stats.duplicate_ratio()
+    }
+}
+
+impl<T: Clone, P: DedupPolicy<T>, C: Clock> StreamingDeduplicator<T, P, C> {
+    pub fn push(&mut self, record: MarketRecord<T>) -> StreamDecision<T> {
+        let now = self.clock.now();
+        self.expire(now);
+        self.stats.seen += 1;
+        let observed = record.observed_at.map_or(now, |observed_at| observed_at.min(now));
+
+        let key = record.key.clone();
+        let Some(held) = self.held.get_mut(&key) else {
+            self.stats.retained += 1;
+            self.insert(key, record.clone(), observed);
+            self.evict_over_capacity();
+            return StreamDecision::Retained(record);
+        };
+
+        if observed >= held.touched_at {
+            self.recency.remove(&(held.touched_at, held.sequence));
+            held.sequence = self.next_sequence;
+            held.touched_at = observed;
+            self.recency.insert((observed, self.next_sequence), key);
+            self.next_sequence += 1;
+        }
+
+        match self.policy.choose(&held.record, &record) {
+            Choice::KeepExisting(reason) => {
+                self.stats.duplicates += 1;
+                StreamDecision::Duplicate(Dropped { record, reason })
+            }
+            Choice::TakeIncoming(reason) => {
+                self.stats.replaced += 1;
+                let previous = std::mem::replace(&mut held.record, record.clone());
+                StreamDecision::Replaced { record, dropped: Dropped { record: previous, reason } }
+            }
+        }
+    }
+
+    /// Drops expired keys without waiting for the next `push`; useful on idle feeds.
+    pub fn purge_expired(&mut self) -> usize {
+        let now = self.clock.now();
+        self.expire(now)
+    }
+
+    fn insert(&mut self, key: DedupKey, record: MarketRecord<T>, touched_at: DateTime<Utc>) {
+        let sequence = self.next_sequence;
+        self.next_sequence += 1;
+        self.recency.insert((touched_at, sequence), key.clone());
+        self.held.insert(key, Held { record, touched_at, sequence });
+    }
+
+    fn expire(&mut self, now: DateTime<Utc>) -> usize {
+        let Some(ttl) = self.config.ttl else {
+            return 0;
+        };
+        let mut expired = 0;
+        while let Some(entry) = self.recency.first_entry() {
+            if now - entry.key().0 < ttl {
+                break;
+            }
+            let key = entry.remove();
+            self.held.remove(&key);
+            expired += 1;
+        }
+        self.stats.expired += expired as u64;
+        expired
+    }
+
+    fn evict_over_capacity(&mut self) {
+        let Some(capacity) = self.config.capacity else {
+            return;
+        };
+        while self.held.len() > capacity {
+            let Some((_, key)) = self.recency.pop_first() else {
+                break;
+            };
+            self.held.remove(&key);
+            self.stats.evicted += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dedup::{BestOdds, DropReason};
+    use crate::market_uid::MarketUid;
+    use chrono::TimeZone;
+
+    const UIDS: [&str; 3] =
+        ["muid-v1-b05bf41737061f9a2d1595d7", "muid-v1-000000000000000000000001", "muid-v1-000000000000000000000002"];
+
+    fn quote(uid: usize, odds: &str) -> MarketRecord<()> {
+        MarketRecord::new(MarketUid::parse(UIDS[uid]).unwrap(), "home", "sx", ()).with_odds(odds.parse().unwrap())
+    }
+
+    fn clock() -> Arc<ManualClock> {
+        Arc::new(ManualClock::new(Utc.with_ymd_and_hms(2024, 8, 10, 16, 0, 0).unwrap()))
+    }
+
+    #[test]
+    fn keys_expire_after_ttl_since_last_touch() {
+        let clock = clock();
+        let config = StreamingConfig::unbounded().with_ttl(Duration::seconds(30));
+        let mut dedup = StreamingDeduplicator::new(config).with_clock(Arc::clone(&clock));
+
+        assert!(!dedup.push(quote(0, "2.0")).is_duplicate());
+        clock.advance(Duration::seconds(20));
+        assert!(dedup.push(quote(0, "2.0")).is_duplicate());
+        clock.advance(Duration::seconds(20));
+        assert!(dedup.push(quote(0, "2.0")).is_duplicate(), "touch at +20s keeps the key alive");
+        clock.advance(Duration::seconds(30));
+        assert!(!dedup.push(quote(0, "2.0")).is_duplicate());
+
+        let stats = dedup.stats();
+        assert_eq!((stats.seen, stats.retained, stats.duplicates, stats.expired), (4, 2, 2, 1));
+        assert_eq!(dedup.duplicate_ratio(), 0.5);
+
+        clock.advance(Duration::minutes(1));
+        assert_eq!(dedup.purge_expired(), 1);
+        assert!(dedup.is_empty());
+    }
+
+    #[test]
+    fn late_quotes_do_not_refresh_the_ttl() {
+        let clock = clock();
+        let start = clock.now();
+        let config = StreamingConfig::unbounded().with_ttl(Duration::seconds(30));
+        let mut dedup = StreamingDeduplicator::new(config).with_clock(Arc::clone(&clock));
+
+        dedup.push(quote(0, "2.0").with_observed_at(start));
+        clock.advance(Duration::seconds(25));
+        assert!(dedup.push(quote(0, "2.0").with_observed_at(start - Duration::seconds(10))).is_duplicate());
+        clock.advance(Duration::seconds(10));
+        assert!(!dedup.push(quote(0, "2.0").with_observed_at(clock.now())).is_duplicate(), "the late quote did not extend the key");
+
+        // A replayed quote already older than the TTL is held only until the next push.
+        dedup.push(quote(1, "2.0").with_observed_at(start));
+        clock.advance(Duration::seconds(1));
+        assert_eq!(dedup.purge_expired(), 1);
+        assert_eq!(dedup.len(), 1);
+    }
+
+    #[test]
+    fn capacity_evicts_least_recently_touched_key() {
+        let mut dedup = StreamingDeduplicator::new(StreamingConfig::unbounded().with_capacity(2)).with_clock(clock());
+        dedup.push(quote(0, "2.0"));
+        dedup.push(quote(1, "2.0"));
+        dedup.push(quote(0, "2.0"));
+        dedup.push(quote(2, "2.0"));
+
+        assert_eq!(dedup.len(), 2);
+        assert_eq!(dedup.stats().evicted, 1);
+        assert!(dedup.push(quote(0, "2.0")).is_duplicate());
+        assert!(!dedup.push(quote(1, "2.0")).is_duplicate());
+    }
+
+    #[test]
+    fn policy_decides_replacements() {
+        let mut dedup = StreamingDeduplicator::new(StreamingConfig::default()).with_policy(BestOdds).with_clock(clock());
+        dedup.push(quote(0, "2.0"));
+        match dedup.push(quote(0, "2.1")) {
+            StreamDecision::Replaced { record, dropped } => {
+                assert_eq!(record.odds, Some("2.1".parse().unwrap()));
+                assert_eq!(dropped.reason, DropReason::WorseOdds);
+            }
+            other => panic!("expected replacement, got {other:?}"),
+        }
+        assert!(matches!(dedup.push(quote(0, "1.9")), StreamDecision::Duplicate(Dropped { reason: DropReason::WorseOdds, .. })));
+        assert_eq!(dedup.stats().replaced, 1);
+    }
+}
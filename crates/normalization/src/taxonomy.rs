@@ -0,0 +1,545 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::market_uid::MarketIdentifier;
+
+/// Sports whose plain "moneyline" / "match winner" market settles with a draw outcome.
+const THREE_WAY_SPORTS: [&str; 2] = ["soccer", "football"];
+
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum TaxonomyError {
+    #[error("unknown market type `{0}`")]
+    UnknownMarketType(String),
+    #[error("unknown period `{0}`")]
+    UnknownPeriod(String),
+    #[error("empty outcome")]
+    EmptyOutcome,
+    #[error("invalid line in outcome `{0}`")]
+    InvalidLine(String),
+    #[error("{family} outcome `{outcome}` requires a line")]
+    MissingLine { family: MarketFamily, outcome: String },
+    #[error("{family} outcome `{outcome}` cannot carry a line")]
+    UnexpectedLine { family: MarketFamily, outcome: String },
+    #[error("side `{side}` is not valid for {family}")]
+    InvalidSide { family: MarketFamily, side: String },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketFamily {
+    #[serde(rename = "moneyline_2way")]
+    Moneyline2Way,
+    #[serde(rename = "moneyline_3way")]
+    Moneyline3Way,
+    DoubleChance,
+    Spread,
+    Total,
+    AsianHandicap,
+}
+
+impl MarketFamily {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MarketFamily::Moneyline2Way => "moneyline_2way",
+            MarketFamily::Moneyline3Way => "moneyline_3way",
+            MarketFamily::DoubleChance => "double_chance",
+            MarketFamily::Spread => "spread",
+            MarketFamily::Total => "total",
+            MarketFamily::AsianHandicap => "asian_handicap",
+        }
+    }
+
+    /// Operator vocabulary (`match_winner`, `total_goals`, `1x2`, …); plain winner markets are
+    /// three-way for sports that settle draws.
+    pub fn parse(sport: &str, market_type: &str) -> Result<Self, TaxonomyError> {
+        let family = match token_key(market_type).as_str() {
+            "moneyline_2way" | "2way" | "two_way" | "12" | "draw_no_bet" => MarketFamily::Moneyline2Way,
+            "moneyline_3way" | "3way" | "three_way" | "1x2" | "full_time_result" => MarketFamily::Moneyline3Way,
+            "moneyline" | "match_winner" | "winner" | "h2h" | "fight_winner" | "match_result" => {
+                if THREE_WAY_SPORTS.contains(&token_key(sport).as_str()) {
+                    MarketFamily::Moneyline3Way
+                } else {
+                    MarketFamily::Moneyline2Way
+                }
+            }
+            "double_chance" | "dc" => MarketFamily::DoubleChance,
+            "spread" | "handicap" | "point_spread" | "run_line" | "puck_line" => MarketFamily::Spread,
+            "asian_handicap" | "ah" => MarketFamily::AsianHandicap,
+            "total" | "totals" | "over_under" | "total_points" | "total_goals" | "total_runs" | "total_games" => {
+                MarketFamily::Total
+            }
+            _ => return Err(TaxonomyError::UnknownMarketType(market_type.to_string())),
+        };
+        Ok(family)
+    }
+
+    fn requires_line(self) -> bool {
+        matches!(self, MarketFamily::Spread | MarketFamily::Total | MarketFamily::AsianHandicap)
+    }
+
+    fn allows(self, side: &Side) -> bool {
+        match (self, side) {
+            (_, Side::Yes | Side::No | Side::Odd | Side::Even) => true,
+            (MarketFamily::Total, side) => matches!(side, Side::Over | Side::Under),
+            // Venues list double-chance sides next to the 1X2 ones, so both families share the six sides.
+            (MarketFamily::Moneyline3Way | MarketFamily::DoubleChance, side) => matches!(
+                side,
+                Side::Home | Side::Draw | Side::Away | Side::HomeOrDraw | Side::DrawOrAway | Side::HomeOrAway
+            ),
+            (MarketFamily::Moneyline2Way | MarketFamily::Spread | MarketFamily::AsianHandicap, side) => {
+                matches!(side, Side::Home | Side::Away | Side::Participant(_))
+            }
+        }
+    }
+}
+
+impl fmt::Display for MarketFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Portion of the event a market settles on. Canonical codes: `ft`, `1h`, `2h`, `q1`…, `p1`…, `s1`….
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Period {
+    FullTime,
+    FirstHalf,
+    SecondHalf,
+    Quarter(u8),
+    /// Ice hockey period.
+    HockeyPeriod(u8),
+    Set(u8),
+}
+
+impl Period {
+    /// Accepts canonical codes and common spellings (`full_game`, `first_half`, `1st_quarter`, `set_2`).
+    pub fn parse(input: &str) -> Result<Self, TaxonomyError> {
+        let key = token_key(input);
+        let unknown = || TaxonomyError::UnknownPeriod(input.to_string());
+        let period = match key.as_str() {
+            "ft" | "full_time" | "fulltime" | "full_game" | "match" | "game" => Period::FullTime,
+            "1h" | "h1" | "first_half" | "1st_half" => Period::FirstHalf,
+            "2h" | "h2" | "second_half" | "2nd_half" => Period::SecondHalf,
+            _ => {
+                let (unit, number) = split_numbered(&key).ok_or_else(unknown)?;
+                match (unit, number) {
+                    ("q" | "quarter", 1..=4) => Period::Quarter(number),
+                    ("p" | "period", 1..=3) => Period::HockeyPeriod(number),
+                    ("s" | "set", 1..=5) => Period::Set(number),
+                    _ => return Err(unknown()),
+                }
+            }
+        };
+        Ok(period)
+    }
+
+    pub fn canonical(self) -> String {
+        match self {
+            Period::FullTime => "ft".to_string(),
+            Period::FirstHalf => "1h".to_string(),
+            Period::SecondHalf => "2h".to_string(),
+            Period::Quarter(n) => format!("q{n}"),
+            Period::HockeyPeriod(n) => format!("p{n}"),
+            Period::Set(n) => format!("s{n}"),
+        }
+    }
+}
+
+impl fmt::Display for Period {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.canonical())
+    }
+}
+
+impl FromStr for Period {
+    type Err = TaxonomyError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse(input)
+    }
+}
+
+impl TryFrom<String> for Period {
+    type Error = TaxonomyError;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Self::parse(&value)
+    }
+}
+
+impl From<Period> for String {
+    fn from(period: Period) -> Self {
+        period.canonical()
+    }
+}
+
+/// Market family plus the period it settles on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MarketSpec {
+    pub family: MarketFamily,
+    pub period: Period,
+}
+
+impl MarketSpec {
+    /// Splits a leading or trailing period (`total_goals_1h`, `first_half_spread`) from the family.
+    pub fn parse(sport: &str, market_type: &str) -> Result<Self, TaxonomyError> {
+        let key = token_key(market_type);
+        let tokens: Vec<&str> = key.split('_').collect();
+        for width in [2, 1] {
+            if tokens.len() <= width {
+                continue;
+            }
+            let (head, tail) = tokens.split_at(width);
+            if let Ok(period) = Period::parse(&head.join("_")) {
+                return Ok(Self { family: MarketFamily::parse(sport, &tail.join("_"))?, period });
+            }
+            let (head, tail) = tokens.split_at(tokens.len() - width);
+            if let Ok(period) = Period::parse(&tail.join("_")) {
+                return Ok(Self { family: MarketFamily::parse(sport, &head.join("_"))?, period });
+            }
+        }
+        Ok(Self { family: MarketFamily::parse(sport, &key)?, period: Period::FullTime })
+    }
+
+    /// `total`, `spread_1h`: full time is implied.
+    pub fn canonical(&self) -> String {
+        match self.period {
+            Period::FullTime => self.family.as_str().to_string(),
+            period => format!("{}_{}", self.family, period),
+        }
+    }
+}
+
+impl fmt::Display for MarketSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.canonical())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Side {
+    Home,
+    Draw,
+    Away,
+    HomeOrDraw,
+    DrawOrAway,
+    HomeOrAway,
+    Over,
+    Under,
+    Yes,
+    No,
+    Odd,
+    Even,
+    /// Named team or player (`lakers`, `djokovic`), lower-case with `_` separators.
+    Participant(String),
+}
+
+impl Side {
+    fn parse(key: &str) -> Self {
+        match key {
+            "home" | "1" | "h" => Side::Home,
+            "draw" | "x" | "tie" => Side::Draw,
+            "away" | "2" | "a" => Side::Away,
+            "home_or_draw" | "1x" => Side::HomeOrDraw,
+            "draw_or_away" | "x2" => Side::DrawOrAway,
+            "home_or_away" | "12" => Side::HomeOrAway,
+            "over" | "o" => Side::Over,
+            "under" | "u" => Side::Under,
+            "yes" => Side::Yes,
+            "no" => Side::No,
+            "odd" => Side::Odd,
+            "even" => Side::Even,
+            participant => Side::Participant(participant.to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Side::Home => "home",
+            Side::Draw => "draw",
+            Side::Away => "away",
+            Side::HomeOrDraw => "home_or_draw",
+            Side::DrawOrAway => "draw_or_away",
+            Side::HomeOrAway => "home_or_away",
+            Side::Over => "over",
+            Side::Under => "under",
+            Side::Yes => "yes",
+            Side::No => "no",
+            Side::Odd => "odd",
+            Side::Even => "even",
+            Side::Participant(name) => name,
+        }
+    }
+
+    /// The side covering every other result, when it does not depend on the participants.
+    fn opposite(&self) -> Option<Side> {
+        let opposite = match self {
+            Side::Home => Side::DrawOrAway,
+            Side::Draw => Side::HomeOrAway,
+            Side::Away => Side::HomeOrDraw,
+            Side::HomeOrDraw => Side::Away,
+            Side::DrawOrAway => Side::Home,
+            Side::HomeOrAway => Side::Draw,
+            Side::Over => Side::Under,
+            Side::Under => Side::Over,
+            Side::Yes => Side::No,
+            Side::No => Side::Yes,
+            Side::Odd => Side::Even,
+            Side::Even => Side::Odd,
+            Side::Participant(_) => return None,
+        };
+        Some(opposite)
+    }
+}
+
+/// A typed outcome. Canonical form: `home`, `over_3.5`, `lakers_m4.5`, `warriors_p4.5`; the sign is
+/// spelt `m`/`p` because fingerprint canonicalisation drops `-` and `+`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Outcome {
+    pub side: Side,
+    pub line: Option<Decimal>,
+}
+
+impl Outcome {
+    pub fn new(side: Side, line: Option<Decimal>) -> Self {
+        Self { side, line: line.map(|line| line.normalize()) }
+    }
+
+    /// Accepts operator spellings: `over_3.5`, `Over 3.5`, `lakers_-4.5`, `lakers_m4.5`, `1X`.
+    pub fn parse(family: MarketFamily, raw: &str) -> Result<Self, TaxonomyError> {
+        let trimmed = raw.trim().to_lowercase();
+        if trimmed.is_empty() {
+            return Err(TaxonomyError::EmptyOutcome);
+        }
+        let tokens: Vec<&str> = trimmed.split(|c: char| c == '_' || c.is_whitespace()).filter(|t| !t.is_empty()).collect();
+        let (side_tokens, line) = match tokens.split_last() {
+            Some((last, rest)) if !rest.is_empty() && looks_like_line(last, rest) => {
+                (rest, Some(parse_line(last).ok_or_else(|| TaxonomyError::InvalidLine(raw.to_string()))?))
+            }
+            _ => (tokens.as_slice(), None),
+        };
+        let side = Side::parse(&side_tokens.join("_"));
+        if !family.allows(&side) {
+            return Err(TaxonomyError::InvalidSide { family, side: side.as_str().to_string() });
+        }
+        // Asian handicap lines move in quarters; -0.25 / -0.75 split the stake across two half-lines at settlement.
+        if family == MarketFamily::AsianHandicap && line.is_some_and(|line| !(line * Decimal::from(4)).fract().is_zero()) {
+            return Err(TaxonomyError::InvalidLine(raw.to_string()));
+        }
+        match (family.requires_line(), line) {
+            (true, None) => Err(TaxonomyError::MissingLine { family, outcome: raw.to_string() }),
+            (false, Some(_)) => Err(TaxonomyError::UnexpectedLine { family, outcome: raw.to_string() }),
+            _ => Ok(Self::new(side, line)),
+        }
+    }
+
+    pub fn canonical(&self) -> String {
+        match self.line {
+            None => self.side.as_str().to_string(),
+            Some(line) if matches!(self.side, Side::Over | Side::Under) => format!("{}_{}", self.side.as_str(), line.abs()),
+            Some(line) if line.is_zero() => format!("{}_0", self.side.as_str()),
+            Some(line) if line.is_sign_negative() => format!("{}_m{}", self.side.as_str(), line.abs()),
+            Some(line) => format!("{}_p{}", self.side.as_str(), line),
+        }
+    }
+
+    /// Complement that does not depend on participant names (`over_3.5` → `under_3.5`,
+    /// `home_m1.5` → `away_p1.5`, `home` → `draw_or_away` in a three-way market).
+    pub fn complement(&self, family: MarketFamily) -> Option<Outcome> {
+        match (family, &self.side) {
+            (_, Side::Participant(_)) => None,
+            (MarketFamily::Spread | MarketFamily::AsianHandicap, Side::Home) => Some(Outcome::new(Side::Away, self.line.map(|l| -l))),
+            (MarketFamily::Spread | MarketFamily::AsianHandicap, Side::Away) => Some(Outcome::new(Side::Home, self.line.map(|l| -l))),
+            (MarketFamily::Moneyline2Way, Side::Home) => Some(Outcome::new(Side::Away, None)),
+            (MarketFamily::Moneyline2Way, Side::Away) => Some(Outcome::new(Side::Home, None)),
+            (_, side) => side.opposite().map(|opposite| Outcome::new(opposite, self.line)),
+        }
+    }
+
+    /// True when backing both outcomes covers every result. Two distinct participants are treated as
+    /// complementary; callers must make sure the market has exactly those two.
+    pub fn is_complement_of(&self, other: &Outcome, family: MarketFamily) -> bool {
+        match (&self.side, &other.side) {
+            (Side::Participant(left), Side::Participant(right)) => {
+                left != right && self.line.map(|l| -l) == other.line
+            }
+            _ => self.complement(family).as_ref() == Some(other),
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.canonical())
+    }
+}
+
+impl MarketIdentifier {
+    /// Rewrites `market_type` and `outcome` to their canonical taxonomy strings before fingerprinting.
+    pub fn with_taxonomy(&self) -> Result<MarketIdentifier, TaxonomyError> {
+        let spec = MarketSpec::parse(&self.sport, &self.market_type)?;
+        let outcome = Outcome::parse(spec.family, &self.outcome)?;
+        Ok(MarketIdentifier { market_type: spec.canonical(), outcome: outcome.canonical(), ..self.clone() })
+    }
+}
+
+/// Lower-case, `_`-joined tokens; keeps `.` so lines survive.
+fn token_key(input: &str) -> String {
+    input
+        .trim()
+        .to_lowercase()
+        .split(|c: char| c == '_' || c == '-' || c == '/' || c.is_whitespace())
+        .filter(|token| !token.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// `q1`, `1q`, `quarter_1`, `1st_quarter`, `first_period` → (`q`/`quarter`/…, 1).
+fn split_numbered(key: &str) -> Option<(&str, u8)> {
+    const ORDINALS: [(&str, u8); 9] =
+        [("first", 1), ("second", 2), ("third", 3), ("fourth", 4), ("fifth", 5), ("1st", 1), ("2nd", 2), ("3rd", 3), ("4th", 4)];
+    if let Some((left, right)) = key.split_once('_') {
+        if let Some(&(_, n)) = ORDINALS.iter().find(|(word, _)| *word == left) {
+            return Some((right, n));
+        }
+        return right.parse().ok().map(|n| (left, n)).or_else(|| left.parse().ok().map(|n| (right, n)));
+    }
+    let digits_at = key.find(|c: char| c.is_ascii_digit())?;
+    let (head, tail) = key.split_at(digits_at);
+    if head.is_empty() {
+        let split = tail.find(|c: char| !c.is_ascii_digit())?;
+        let (number, unit) = tail.split_at(split);
+        return number.parse().ok().map(|n| (unit, n));
+    }
+    tail.parse().ok().map(|n| (head, n))
+}
+
+/// A trailing number is a line when it is signed, has a decimal point, is the level `0`, or follows
+/// `over`/`under`; other bare integers end team names (`schalke 04`, `hannover_96`).
+fn looks_like_line(token: &str, side_tokens: &[&str]) -> bool {
+    let unsigned = token.trim_start_matches(['+', '-', 'm', 'p']);
+    let numeric =
+        !unsigned.is_empty() && unsigned.chars().all(|c| c.is_ascii_digit() || c == '.') && unsigned.starts_with(|c: char| c.is_ascii_digit());
+    let explicit = unsigned.len() < token.len() || unsigned.contains('.') || unsigned == "0";
+    numeric && (explicit || matches!(Side::parse(&side_tokens.join("_")), Side::Over | Side::Under))
+}
+
+fn parse_line(token: &str) -> Option<Decimal> {
+    let (negative, unsigned) = match token.as_bytes().first()? {
+        b'-' | b'm' => (true, &token[1..]),
+        b'+' | b'p' => (false, &token[1..]),
+        _ => (false, token),
+    };
+    let value = Decimal::from_str(unsigned).ok()?;
+    Some(if negative { -value } else { value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn parses_operator_market_types() {
+        assert_eq!(MarketSpec::parse("Soccer", "moneyline").unwrap().family, MarketFamily::Moneyline3Way);
+        assert_eq!(MarketSpec::parse("Basketball", "moneyline").unwrap().family, MarketFamily::Moneyline2Way);
+        let spec = MarketSpec::parse("Soccer", "total_goals_1h").unwrap();
+        assert_eq!((spec.family, spec.period), (MarketFamily::Total, Period::FirstHalf));
+        assert_eq!(spec.canonical(), "total_1h");
+        assert_eq!(MarketSpec::parse("Basketball", "1st quarter spread").unwrap().canonical(), "spread_q1");
+        assert_eq!(MarketSpec::parse("Ice Hockey", "total_goals").unwrap().canonical(), "total");
+        assert!(matches!(MarketSpec::parse("Soccer", "corners"), Err(TaxonomyError::UnknownMarketType(_))));
+        assert_eq!(Period::parse("full_game").unwrap(), Period::FullTime);
+        assert_eq!(Period::parse("third_period").unwrap(), Period::HockeyPeriod(3));
+        assert_eq!(Period::parse("set_2").unwrap(), Period::Set(2));
+    }
+
+    #[test]
+    fn parses_outcomes_with_decimal_lines() {
+        let over = Outcome::parse(MarketFamily::Total, "Over 3.50").unwrap();
+        assert_eq!(over, Outcome::new(Side::Over, Some(dec("3.5"))));
+        assert_eq!(over.canonical(), "over_3.5");
+
+        let lakers = Outcome::parse(MarketFamily::Spread, "lakers_-4.5").unwrap();
+        assert_eq!(lakers.side, Side::Participant("lakers".into()));
+        assert_eq!(lakers.line, Some(dec("-4.5")));
+        assert_eq!(lakers.canonical(), "lakers_m4.5");
+        assert_eq!(Outcome::parse(MarketFamily::Spread, "lakers_m4.5").unwrap(), lakers);
+        assert_eq!(Outcome::parse(MarketFamily::Spread, "la_rochelle_+6.5").unwrap().canonical(), "la_rochelle_p6.5");
+        assert_eq!(Outcome::parse(MarketFamily::DoubleChance, "1X").unwrap().side, Side::HomeOrDraw);
+        assert_eq!(Outcome::parse(MarketFamily::Moneyline2Way, "49ers").unwrap().side, Side::Participant("49ers".into()));
+
+        assert!(matches!(Outcome::parse(MarketFamily::Total, "over"), Err(TaxonomyError::MissingLine { .. })));
+        assert!(matches!(Outcome::parse(MarketFamily::Moneyline3Way, "home_1.5"), Err(TaxonomyError::UnexpectedLine { .. })));
+        assert!(matches!(Outcome::parse(MarketFamily::Total, "home_1.5"), Err(TaxonomyError::InvalidSide { .. })));
+        assert_eq!(Outcome::parse(MarketFamily::AsianHandicap, "home_-0.75").unwrap().line, Some(dec("-0.75")));
+        assert!(matches!(Outcome::parse(MarketFamily::AsianHandicap, "home_-0.3"), Err(TaxonomyError::InvalidLine(_))));
+    }
+
+    #[test]
+    fn trailing_integers_of_team_names_are_not_lines() {
+        for name in ["schalke 04", "hannover_96", "Mainz 05"] {
+            let outcome = Outcome::parse(MarketFamily::Moneyline2Way, name).unwrap();
+            assert_eq!(outcome.side, Side::Participant(name.to_lowercase().replace(' ', "_")));
+            assert_eq!(outcome.line, None);
+        }
+        assert!(matches!(Outcome::parse(MarketFamily::Spread, "schalke_04"), Err(TaxonomyError::MissingLine { .. })));
+        let schalke = Outcome::parse(MarketFamily::Spread, "schalke_04_-1.5").unwrap();
+        assert_eq!((schalke.side, schalke.line), (Side::Participant("schalke_04".into()), Some(dec("-1.5"))));
+        assert_eq!(Outcome::parse(MarketFamily::Spread, "hannover 96 +1").unwrap().line, Some(dec("1")));
+        assert_eq!(Outcome::parse(MarketFamily::Total, "over 3").unwrap().line, Some(dec("3")));
+        assert_eq!(Outcome::parse(MarketFamily::AsianHandicap, "home_0").unwrap().line, Some(Decimal::ZERO));
+    }
+
+    #[test]
+    fn complements_are_methods() {
+        let over = Outcome::new(Side::Over, Some(dec("217.5")));
+        assert_eq!(over.complement(MarketFamily::Total), Some(Outcome::new(Side::Under, Some(dec("217.5")))));
+        assert!(!over.is_complement_of(&Outcome::new(Side::Under, Some(dec("218.5"))), MarketFamily::Total));
+
+        let home = Outcome::new(Side::Home, Some(dec("-0.25")));
+        assert_eq!(home.complement(MarketFamily::AsianHandicap), Some(Outcome::new(Side::Away, Some(dec("0.25")))));
+        assert_eq!(Outcome::new(Side::Home, None).complement(MarketFamily::Moneyline3Way), Some(Outcome::new(Side::DrawOrAway, None)));
+        assert_eq!(Outcome::new(Side::Home, None).complement(MarketFamily::Moneyline2Way), Some(Outcome::new(Side::Away, None)));
+
+        let lakers = Outcome::new(Side::Participant("lakers".into()), Some(dec("-4.5")));
+        let warriors = Outcome::new(Side::Participant("warriors".into()), Some(dec("4.5")));
+        assert_eq!(lakers.complement(MarketFamily::Spread), None);
+        assert!(lakers.is_complement_of(&warriors, MarketFamily::Spread));
+        assert!(!lakers.is_complement_of(&Outcome::new(Side::Participant("warriors".into()), Some(dec("-4.5"))), MarketFamily::Spread));
+    }
+
+    #[test]
+    fn canonical_form_feeds_the_fingerprint() {
+        use crate::market_uid::MarketUid;
+        use chrono::TimeZone;
+
+        let identifier = |market_type: &str, outcome: &str| MarketIdentifier {
+            operator: "sx".into(),
+            sport: "Basketball".into(),
+            league: "NBA".into(),
+            event: "Lakers vs Warriors".into(),
+            market_type: market_type.into(),
+            outcome: outcome.into(),
+            event_timestamp: chrono::Utc.with_ymd_and_hms(2024, 11, 5, 2, 0, 0).unwrap(),
+            variant: None,
+            ladder: None,
+        };
+        let uid = |market_type: &str, outcome: &str| {
+            MarketUid::from_identifier(&identifier(market_type, outcome).with_taxonomy().unwrap()).unwrap()
+        };
+
+        assert_eq!(uid("total_points", "Over 217.50"), uid("totals", "over_217.5"));
+        assert_ne!(uid("spread", "lakers_-4.5"), uid("spread", "lakers_+4.5"));
+        assert_eq!(identifier("spread", "lakers_-4.5").with_taxonomy().unwrap().outcome, "lakers_m4.5");
+    }
+}
@@ -0,0 +1,100 @@
+use chrono::TimeZone;
+use normalization::{
+    AliasDictionary, AliasError, AliasKind, AliasResolver, FuzzyConfig, MarketIdentifier, MarketKey, Resolution,
+    UidVersion,
+};
+
+fn resolver() -> AliasResolver {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../data/aliases/aliases.yml");
+    AliasResolver::new(AliasDictionary::load(path).expect("alias dictionary"))
+}
+
+fn identifier(operator: &str, league: &str, event: &str) -> MarketIdentifier {
+    MarketIdentifier {
+        operator: operator.into(),
+        sport: "Soccer".into(),
+        league: league.into(),
+        event: event.into(),
+        market_type: "moneyline".into(),
+        outcome: "home".into(),
+        event_timestamp: chrono::Utc.with_ymd_and_hms(2024, 9, 1, 15, 0, 0).unwrap(),
+        variant: Some("pre".into()),
+        ladder: None,
+    }
+}
+
+#[test]
+fn aliases_converge_before_fingerprinting() {
+    let resolver = resolver();
+    let sx = identifier("sx", "EPL", "Man Utd vs Chelsea FC");
+    let azuro = identifier("azuro", "English Premier League", "Manchester Utd FC - Chelsea");
+    assert_ne!(MarketKey::from_identifier(&sx, UidVersion::V1).unwrap(), MarketKey::from_identifier(&azuro, UidVersion::V1).unwrap());
+
+    let sx = sx.with_aliases(&resolver);
+    let azuro = azuro.with_aliases(&resolver);
+    assert_eq!(sx.league, "Premier League");
+    assert_eq!(sx.event, "Manchester United vs Chelsea");
+    assert_eq!(MarketKey::from_identifier(&sx, UidVersion::V1).unwrap(), MarketKey::from_identifier(&azuro, UidVersion::V1).unwrap());
+    assert!(resolver.review_queue().is_empty());
+}
+
+#[test]
+fn paris_fc_is_not_merged_into_psg() {
+    let resolver = resolver();
+    assert!(matches!(
+        resolver.resolve("soccer", AliasKind::Team, "Paris SG"),
+        Resolution::Resolved(found) if found.canonical == "PSG"
+    ));
+    let paris_fc = resolver.resolve("soccer", AliasKind::Team, "Paris FC");
+    assert!(!matches!(&paris_fc, Resolution::Resolved(found) if found.canonical == "PSG"), "{paris_fc:?}");
+
+    let psg = identifier("sx", "Ligue 1", "Paris Saint-Germain vs Marseille").with_aliases(&resolver);
+    let paris_fc = identifier("sx", "Ligue 1", "Paris FC vs Marseille").with_aliases(&resolver);
+    assert_ne!(
+        MarketKey::from_identifier(&psg, UidVersion::V1).unwrap(),
+        MarketKey::from_identifier(&paris_fc, UidVersion::V1).unwrap()
+    );
+}
+
+#[test]
+fn fuzzy_fallback_accepts_close_spellings_and_queues_doubtful_ones() {
+    let resolver = resolver();
+    match resolver.resolve("soccer", AliasKind::Team, "Manchester Untied") {
+        Resolution::Resolved(found) => {
+            assert_eq!(found.canonical, "Manchester United");
+            assert!(found.score >= FuzzyConfig::default().accept);
+        }
+        other => panic!("expected fuzzy resolution, got {other:?}"),
+    }
+
+    let unchanged = identifier("sx", "Premier League", "Manchester vs Chelsea").with_aliases(&resolver);
+    assert_eq!(unchanged.event, "Manchester vs Chelsea");
+    let pending = resolver.review_queue().pending();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].raw, "Manchester");
+    assert!(pending[0].ambiguous);
+    assert_eq!(pending[0].dictionary_version, 2);
+
+    assert_eq!(resolver.resolve("soccer", AliasKind::Team, "Tottenham"), Resolution::Unknown);
+    assert_eq!(resolver.resolve("curling", AliasKind::Team, "Arsenal"), Resolution::Unknown);
+}
+
+#[test]
+fn csv_dictionaries_merge_and_reject_conflicts() {
+    let csv = "version,sport,kind,canonical,alias\n2,soccer,team,Tottenham,Spurs\n2,soccer,team,Tottenham,Tottenham Hotspur\n";
+    let extra = AliasDictionary::from_csv_reader(csv.as_bytes()).expect("csv dictionary");
+    assert_eq!(extra.len(), 1);
+
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../data/aliases/aliases.yml");
+    let mut dictionary = AliasDictionary::load(path).expect("alias dictionary");
+    dictionary.merge(extra).expect("merge");
+    let resolver = AliasResolver::new(dictionary);
+    assert_eq!(resolver.canonical_name("Soccer", AliasKind::Team, "spurs"), "Tottenham");
+
+    let conflicting = "version,sport,kind,canonical,alias\n1,soccer,team,Arsenal,Gunners\n1,soccer,team,Tottenham,Gunners\n";
+    let err = AliasDictionary::from_csv_reader(conflicting.as_bytes()).unwrap_err();
+    assert!(matches!(err, AliasError::Conflict { kind: AliasKind::Team, .. }));
+
+    let mixed = "version,sport,kind,canonical,alias\n1,soccer,team,Arsenal,Gunners\n2,soccer,team,Chelsea,Blues\n";
+    assert!(matches!(AliasDictionary::from_csv_reader(mixed.as_bytes()), Err(AliasError::VersionMismatch(1, 2))));
+}
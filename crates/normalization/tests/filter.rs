@@ -0,0 +1,75 @@
+use chrono::{Duration, TimeZone, Utc};
+use normalization::{FilterDecision, MarketFilter, MarketFilterHandle, MarketIdentifier, MarketRecord, MarketUid, RejectReason};
+
+const RISK_CONFIG: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../config/risk.yml");
+
+fn identifier(operator: &str, sport: &str, league: &str, market_type: &str) -> MarketIdentifier {
+    MarketIdentifier {
+        operator: operator.into(),
+        sport: sport.into(),
+        league: league.into(),
+        event: "Arsenal vs Chelsea".into(),
+        market_type: market_type.into(),
+        outcome: "home".into(),
+        event_timestamp: Utc.with_ymd_and_hms(2024, 8, 10, 16, 30, 0).unwrap(),
+        variant: Some("pre".into()),
+        ladder: None,
+    }
+}
+
+fn keyed(identifier: MarketIdentifier) -> (MarketIdentifier, MarketRecord<String>) {
+    let uid = MarketUid::from_identifier(&identifier).expect("uid generation");
+    let record = MarketRecord::new(uid, &identifier.outcome, identifier.operator.clone(), identifier.league.clone());
+    (identifier, record)
+}
+
+#[test]
+fn committed_risk_config_enforces_the_perimeter() {
+    let filter = MarketFilter::load(RISK_CONFIG).expect("risk config");
+    let now = Utc.with_ymd_and_hms(2024, 8, 10, 12, 0, 0).unwrap();
+    let result = filter.apply(
+        vec![
+            keyed(identifier("sx", "Soccer", "Premier League", "1x2")),
+            keyed(identifier("sx", "Soccer", "SIM", "1x2")),
+            keyed(identifier("azuro", "Soccer", "Serie A", "1x2")),
+            keyed(identifier("azuro", "Basketball", "NBA", "player-props")),
+            keyed(identifier("azuro", "Basketball", "NBA", "total")),
+        ],
+        now,
+    );
+
+    let admitted: Vec<_> = result.admitted.iter().map(|(_, record)| record.payload.as_str()).collect();
+    assert_eq!(admitted, ["Premier League", "NBA"]);
+    let reasons: Vec<_> = result.rejected.iter().map(|rejected| rejected.reason.clone()).collect();
+    assert!(matches!(reasons[0], RejectReason::LeagueExcluded { .. }));
+    assert!(matches!(reasons[1], RejectReason::LeagueNotIncluded { .. }));
+    assert!(matches!(reasons[2], RejectReason::MarketExcluded { .. }));
+
+    let kickoff = identifier("sx", "Soccer", "Premier League", "1x2");
+    assert!(matches!(
+        filter.evaluate(&kickoff, kickoff.event_timestamp - Duration::minutes(2)),
+        FilterDecision::Rejected(RejectReason::PastCutoff { .. })
+    ));
+}
+
+#[test]
+fn handle_reloads_and_keeps_last_good_filter() {
+    let path = std::env::temp_dir().join(format!("otter-risk-{}.yml", std::process::id()));
+    std::fs::write(&path, "markets:\n  excluded_leagues: [NBA]\n").unwrap();
+    let handle = MarketFilterHandle::load(&path).unwrap();
+    let nba = identifier("sx", "Basketball", "NBA", "total");
+    let now = Utc.with_ymd_and_hms(2024, 8, 10, 12, 0, 0).unwrap();
+    assert!(matches!(handle.current().evaluate(&nba, now), FilterDecision::Rejected(_)));
+
+    let before = handle.current();
+    std::fs::write(&path, "markets:\n  excluded_leagues: []\n").unwrap();
+    handle.reload().unwrap();
+    assert_eq!(handle.current().evaluate(&nba, now), FilterDecision::Admitted);
+    assert!(matches!(before.evaluate(&nba, now), FilterDecision::Rejected(_)), "held snapshots are unaffected");
+
+    std::fs::write(&path, "markets: [broken\n").unwrap();
+    assert!(handle.reload().is_err());
+    assert_eq!(handle.current().evaluate(&nba, now), FilterDecision::Admitted);
+
+    std::fs::remove_file(&path).unwrap();
+}
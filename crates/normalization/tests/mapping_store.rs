@@ -0,0 +1,116 @@
+use chrono::TimeZone;
+use normalization::{
+    pair_markets, pair_markets_with_overrides, MappingSource, MappingStatus, MappingStore, MarketIdentifier, MarketRecord,
+    MarketUid, PairingOptions, UidVersion,
+};
+
+fn identifier(operator: &str, league: &str, event: &str, outcome: &str) -> MarketIdentifier {
+    MarketIdentifier {
+        operator: operator.into(),
+        sport: "basketball".into(),
+        league: league.into(),
+        event: event.into(),
+        market_type: "total_points".into(),
+        outcome: outcome.into(),
+        event_timestamp: chrono::Utc.with_ymd_and_hms(2024, 12, 1, 0, 30, 0).unwrap(),
+        variant: Some("pre".into()),
+        ladder: None,
+    }
+}
+
+fn keyed(identifier: MarketIdentifier) -> (MarketIdentifier, MarketRecord<String>) {
+    let uid = MarketUid::from_identifier(&identifier).expect("uid generation");
+    let payload = format!("{}:{}", identifier.operator, identifier.event);
+    let record = MarketRecord::new(uid, &identifier.outcome, identifier.operator.clone(), payload);
+    (identifier, record)
+}
+
+fn records() -> Vec<(MarketIdentifier, MarketRecord<String>)> {
+    vec![
+        keyed(identifier("sx", "NBA", "Celtics vs Heat", "over_217.5")),
+        keyed(identifier("azuro", "NBA", "Celtics vs Heat", "under_217.5")),
+        keyed(identifier("sx", "NBA", "Bulls vs Knicks", "over_210.5")),
+        keyed(identifier("azuro", "National Basketball Association", "Chicago Bulls vs New York Knicks", "under_210.5")),
+    ]
+}
+
+fn uid(records: &[(MarketIdentifier, MarketRecord<String>)], index: usize) -> MarketUid {
+    records[index].1.key.uid.clone()
+}
+
+#[test]
+fn manual_overrides_take_precedence_over_automatic_matching() {
+    let records = records();
+    let at = chrono::Utc.with_ymd_and_hms(2024, 11, 30, 12, 0, 0).unwrap();
+    let store = MappingStore::open_in_memory().unwrap();
+
+    let automatic = pair_markets(records.clone(), UidVersion::V1).unwrap();
+    assert_eq!(automatic.pairs.len(), 1);
+    assert_eq!(store.record_pairs(&automatic.pairs, at).unwrap(), 1);
+
+    store.reject(&uid(&records, 0), &uid(&records, 1), "ops@otter", at).unwrap();
+    store.confirm(&uid(&records, 3), &uid(&records, 2), "ops@otter", at).unwrap();
+
+    let overrides = store.overrides().unwrap();
+    let result = pair_markets_with_overrides(records.clone(), PairingOptions::new(UidVersion::V1), &overrides).unwrap();
+    assert_eq!(result.pairs.len(), 1);
+    assert_eq!(result.pairs[0].left.record.payload, "sx:Bulls vs Knicks");
+    assert_eq!(result.pairs[0].right.record.payload, "azuro:Chicago Bulls vs New York Knicks");
+    assert_eq!(result.unmatched.len(), 2);
+
+    let effective = store.effective(&uid(&records, 1), &uid(&records, 0)).unwrap().expect("entry");
+    assert_eq!((effective.source, effective.status), (MappingSource::Manual, MappingStatus::Rejected));
+    assert_eq!(store.entries_for(&uid(&records, 0)).unwrap().len(), 2);
+
+    assert!(store.clear_override(&uid(&records, 0), &uid(&records, 1)).unwrap());
+    let effective = store.effective(&uid(&records, 0), &uid(&records, 1)).unwrap().expect("entry");
+    assert_eq!((effective.source, effective.status), (MappingSource::Automatic, MappingStatus::Confirmed));
+}
+
+#[test]
+fn confirmed_pairs_must_be_complementary() {
+    let records = vec![
+        keyed(identifier("sx", "NBA", "Celtics vs Heat", "over_217.5")),
+        keyed(identifier("azuro", "NBA", "Celtics vs Heat", "over_217.5")),
+        keyed(identifier("azuro", "NBA", "Celtics vs Heat", "under_217.5")),
+    ];
+    let at = chrono::Utc.with_ymd_and_hms(2024, 11, 30, 12, 0, 0).unwrap();
+    let store = MappingStore::open_in_memory().unwrap();
+    store.confirm(&uid(&records, 0), &uid(&records, 1), "ops@otter", at).unwrap();
+
+    let overrides = store.overrides().unwrap();
+    let result = pair_markets_with_overrides(records, PairingOptions::new(UidVersion::V1), &overrides).unwrap();
+    assert_eq!(result.pairs.len(), 1);
+    assert_eq!(result.pairs[0].left.outcome.canonical(), "over_217.5");
+    assert_eq!(result.pairs[0].right.outcome.canonical(), "under_217.5");
+    assert_eq!(result.unmatched.len(), 1);
+}
+
+#[test]
+fn store_persists_and_round_trips_through_export() {
+    let records = records();
+    let at = chrono::Utc.with_ymd_and_hms(2024, 11, 30, 12, 0, 0).unwrap();
+    let path = std::env::temp_dir().join(format!("otter-mappings-{}.sqlite", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    {
+        let store = MappingStore::open(&path).unwrap();
+        store.confirm(&uid(&records, 2), &uid(&records, 3), "ops@otter", at).unwrap();
+        store.reject(&uid(&records, 0), &uid(&records, 3), "risk@otter", at).unwrap();
+    }
+    let reopened = MappingStore::open(&path).unwrap();
+    let entries = reopened.entries().unwrap();
+    assert_eq!(entries.len(), 2);
+
+    let mut exported = Vec::new();
+    assert_eq!(reopened.export(&mut exported).unwrap(), 2);
+    let header = String::from_utf8(exported.clone()).unwrap();
+    assert!(header.starts_with("left_uid,right_uid,source,status,decided_by,decided_at,note"));
+
+    let mut copy = MappingStore::open_in_memory().unwrap();
+    assert_eq!(copy.import(exported.as_slice()).unwrap(), 2);
+    assert_eq!(copy.entries().unwrap(), entries);
+
+    drop(reopened);
+    std::fs::remove_file(&path).unwrap();
+}
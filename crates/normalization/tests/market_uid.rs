@@ -1,7 +1,8 @@
 use chrono::TimeZone;
 use normalization::{
-    dedup::{deduplicate, MarketRecord},
-    MarketIdentifier, MarketUid,
+    build_mapping,
+    dedup::{deduplicate, deduplicate_with_migration, FirstSeen, MarketRecord, SourcePriority},
+    read_mapping, read_seed, MarketCatalog, MarketIdentifier, MarketUid, SeedCatalogue, SeedEntry, UidMigration, UidVersion,
 };
 use proptest::prelude::*;
 use proptest::string::string_regex;
@@ -123,6 +124,85 @@ fn deduplication_drops_duplicates_by_uid_and_side() {
     assert_eq!(result.duplicates[0].source, "azuro");
 }
 
+fn seed_path() -> &'static str {
+    concat!(env!("CARGO_MANIFEST_DIR"), "/../../data/market_uid_seed.csv")
+}
+
+fn seed() -> Vec<SeedEntry> {
+    read_seed(std::fs::File::open(seed_path()).expect("seed csv")).expect("seed rows")
+}
+
+#[test]
+fn seed_uids_reproduce_with_v1() {
+    let seed = seed();
+    assert_eq!(seed.len(), 50);
+    for entry in &seed {
+        let uid = MarketUid::from_identifier(&entry.identifier).expect("uid generation");
+        assert_eq!(uid, entry.market_uid);
+    }
+}
+
+#[test]
+fn committed_seed_validates_clean() {
+    let catalogue = SeedCatalogue::read(std::fs::File::open(seed_path()).expect("seed csv")).expect("seed rows");
+    let report = catalogue.validate(UidVersion::V1);
+    assert_eq!(report.rows, 50);
+    assert!(report.is_clean(), "{:?}", report.issues);
+}
+
+#[test]
+fn seed_catalog_resolves_logged_uids() {
+    let catalog = MarketCatalog::from_seed(std::fs::File::open(seed_path()).expect("seed csv")).expect("seed catalog");
+    assert_eq!(catalog.len(), 50);
+    let uid = MarketUid::parse("muid-v1-b05bf41737061f9a2d1595d7").unwrap();
+    assert_eq!(catalog.describe(&uid), "sx · Premier League · Arsenal vs Chelsea · moneyline/home · 2024-08-10 16:30Z");
+    assert_eq!(catalog.get(&uid).unwrap().fingerprint, catalog.identifier(&uid).unwrap().canonical_fingerprint().unwrap());
+}
+
+#[test]
+fn seed_markets_fit_the_taxonomy() {
+    for entry in seed() {
+        let typed = entry.identifier.with_taxonomy();
+        assert!(typed.is_ok(), "{:?}: {:?}", entry.identifier, typed.err());
+    }
+}
+
+#[test]
+fn committed_v1_to_v2_mapping_matches_seed() {
+    let mappings = build_mapping(&seed(), UidVersion::V1, UidVersion::V2).expect("mapping");
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../data/market_uid_v1_to_v2.csv");
+    let committed = read_mapping(std::fs::File::open(path).expect("mapping csv")).expect("mapping rows");
+    assert_eq!(committed, mappings);
+}
+
+#[test]
+fn dedup_treats_v1_and_v2_uids_as_equal_during_migration() {
+    let seed = seed();
+    let entry = &seed[0];
+    let v2 = MarketUid::from_identifier_versioned(&entry.identifier, UidVersion::V2).expect("uid generation");
+    let mappings = build_mapping(&seed, UidVersion::V1, UidVersion::V2).expect("mapping");
+    let window_end = chrono::Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap();
+    let migration = UidMigration::new(mappings, window_end).expect("migration");
+    let records = || {
+        [
+            MarketRecord::new(v2.clone(), "home", "sx", "v2".to_string()),
+            MarketRecord::new(entry.market_uid.clone(), "home", "azuro", "v1".to_string()),
+        ]
+    };
+
+    let during = deduplicate_with_migration(records(), &FirstSeen, &migration, window_end - chrono::Duration::hours(1));
+    assert_eq!(during.retained.len(), 1);
+    assert_eq!(during.duplicates[0].payload, "v1");
+
+    let ranked = deduplicate_with_migration(records(), &SourcePriority::new(["azuro"]), &migration, window_end - chrono::Duration::hours(1));
+    assert_eq!(ranked.retained[0].payload, "v1");
+    assert_eq!(ranked.duplicates[0].payload, "v2");
+
+    let after = deduplicate_with_migration(records(), &FirstSeen, &migration, window_end);
+    assert!(after.is_clean());
+    assert!(deduplicate(records()).is_clean());
+}
+
 proptest! {
     #[test]
     fn uid_generation_is_idempotent(identifier in identifier_strategy()) {
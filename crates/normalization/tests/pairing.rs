@@ -0,0 +1,172 @@
+use chrono::TimeZone;
+use chrono::Duration;
+use normalization::{
+    pair_markets, pair_markets_with, MarketFamily, MarketIdentifier, MarketRecord, MarketUid, PairingOptions, Side, UidVersion,
+};
+
+fn identifier(operator: &str, event: &str, market_type: &str, outcome: &str, ladder: Option<&str>) -> MarketIdentifier {
+    MarketIdentifier {
+        operator: operator.into(),
+        sport: "basketball".into(),
+        league: "NBA".into(),
+        event: event.into(),
+        market_type: market_type.into(),
+        outcome: outcome.into(),
+        event_timestamp: chrono::Utc.with_ymd_and_hms(2024, 11, 5, 2, 0, 0).unwrap(),
+        variant: Some("pre".into()),
+        ladder: ladder.map(str::to_string),
+    }
+}
+
+fn keyed(identifier: MarketIdentifier) -> (MarketIdentifier, MarketRecord<String>) {
+    keyed_at(identifier, UidVersion::V1)
+}
+
+fn keyed_at(identifier: MarketIdentifier, version: UidVersion) -> (MarketIdentifier, MarketRecord<String>) {
+    let uid = MarketUid::from_identifier_versioned(&identifier, version).expect("uid generation");
+    let payload = format!("{}:{}", identifier.operator, identifier.outcome);
+    let record = MarketRecord::new(uid, &identifier.outcome, identifier.operator.clone(), payload);
+    (identifier, record)
+}
+
+#[test]
+fn pairs_complementary_outcomes_across_operators() {
+    let records = vec![
+        keyed(identifier("sx", "Lakers vs Warriors", "spread", "lakers_-4.5", Some("0.5"))),
+        keyed(identifier("azuro", "Lakers  vs Warriors", "spread", "warriors_+4.5", Some("0.01"))),
+        keyed(identifier("sx", "Celtics vs Heat", "total_points", "over_217.5", None)),
+        keyed(identifier("azuro", "Celtics vs Heat", "total_points", "under_217.5", None)),
+        keyed(identifier("azuro", "Celtics vs Heat", "total_points", "under_218.5", None)),
+    ];
+
+    let result = pair_markets(records, UidVersion::V1).expect("pairing");
+
+    assert_eq!(result.pairs.len(), 2);
+    let spread = &result.pairs[0];
+    assert_eq!(spread.left.operator, "sx");
+    assert_eq!(spread.right.operator, "azuro");
+    assert_eq!(spread.left.outcome.to_string(), "lakers_m4.5");
+    assert_eq!(spread.right.outcome.to_string(), "warriors_p4.5");
+    assert_eq!(spread.market.family, MarketFamily::Spread);
+    assert!(spread.market_key.as_str().starts_with("mkey-v1-"));
+    assert_eq!(result.pairs[1].right.record.payload, "azuro:under_217.5");
+
+    assert_eq!(result.unmatched.len(), 1);
+    assert_eq!(result.unmatched[0].payload, "azuro:under_218.5");
+}
+
+#[test]
+fn synonymous_market_types_are_grouped_by_taxonomy() {
+    let records = vec![
+        keyed(identifier("sx", "Celtics vs Heat", "totals", "over_217.5", None)),
+        keyed(identifier("azuro", "Celtics vs Heat", "total_points", "under_217.5", None)),
+        keyed(identifier("sx", "Celtics vs Heat", "1st half spread", "home_-2.5", None)),
+        keyed(identifier("azuro", "Celtics vs Heat", "spread_1h", "away_+2.5", None)),
+    ];
+
+    let result = pair_markets(records, UidVersion::V1).expect("pairing");
+
+    assert_eq!(result.pairs.len(), 2, "unmatched: {:?}", result.unmatched);
+    assert_eq!(result.pairs[0].market.family, MarketFamily::Total);
+    assert_eq!(result.pairs[1].market.canonical(), "spread_1h");
+    assert!(result.unmatched.is_empty());
+}
+
+#[test]
+fn same_operator_and_same_outcome_are_not_paired() {
+    let records = vec![
+        keyed(identifier("sx", "Bulls vs Knicks", "moneyline", "home", None)),
+        keyed(identifier("sx", "Bulls vs Knicks", "moneyline", "away", None)),
+        keyed(identifier("azuro", "Bulls vs Knicks", "moneyline", "home", None)),
+        keyed(identifier("azuro", "Nets vs Magic", "moneyline", "away", None)),
+    ];
+
+    let result = pair_markets(records, UidVersion::V1).expect("pairing");
+
+    assert_eq!(result.pairs.len(), 1);
+    assert_eq!(result.pairs[0].left.record.payload, "sx:away");
+    assert_eq!(result.pairs[0].right.record.payload, "azuro:home");
+    let unmatched: Vec<_> = result.unmatched.iter().map(|r| r.payload.as_str()).collect();
+    assert_eq!(unmatched, vec!["sx:home", "azuro:away"]);
+    assert!((result.match_ratio(4) - 0.5).abs() < f64::EPSILON);
+}
+
+#[test]
+fn three_way_markets_pair_only_with_double_chance_complements() {
+    let records = vec![
+        keyed(identifier("sx", "Arsenal vs Chelsea", "moneyline", "home", None)),
+        keyed(identifier("azuro", "Arsenal vs Chelsea", "moneyline", "away", None)),
+        keyed(identifier("azuro", "Arsenal vs Chelsea", "moneyline", "draw", None)),
+        keyed(identifier("azuro", "Arsenal vs Chelsea", "moneyline", "draw_or_away", None)),
+    ];
+
+    let result = pair_markets(records, UidVersion::V1).expect("pairing");
+
+    assert_eq!(result.pairs.len(), 1);
+    assert_eq!(result.pairs[0].right.outcome.side, Side::DrawOrAway);
+    assert_eq!(result.unmatched.len(), 2);
+}
+
+#[test]
+fn records_with_v1_and_v2_uids_still_pair() {
+    let records = vec![
+        keyed_at(identifier("sx", "Celtics vs Heat", "total_points", "over_217.5", None), UidVersion::V1),
+        keyed_at(identifier("azuro", "Celtics vs Heat", "total_points", "under_217.5", None), UidVersion::V2),
+    ];
+
+    let result = pair_markets(records, UidVersion::V2).expect("pairing");
+
+    assert_eq!(result.pairs.len(), 1);
+    assert!(result.pairs[0].market_key.as_str().starts_with("mkey-v2-"));
+    assert_eq!(result.pairs[0].left.record.key.uid.version(), UidVersion::V1);
+    assert!(result.unmatched.is_empty());
+}
+
+fn kicking_off(mut identifier: MarketIdentifier, minutes: i64) -> MarketIdentifier {
+    identifier.event_timestamp += Duration::minutes(minutes);
+    identifier
+}
+
+#[test]
+fn kickoff_tolerance_pairs_venues_a_few_minutes_apart() {
+    let records = || {
+        vec![
+            keyed(identifier("sx", "Celtics vs Heat", "total_points", "over_217.5", None)),
+            keyed(kicking_off(identifier("azuro", "Celtics vs Heat", "total_points", "under_217.5", None), 7)),
+            keyed(kicking_off(identifier("azuro", "Celtics vs Heat", "total_points", "under_217.5", None), 90)),
+        ]
+    };
+
+    let exact = pair_markets(records(), UidVersion::V1).expect("pairing");
+    assert!(exact.pairs.is_empty());
+    assert_eq!(exact.unmatched.len(), 3);
+
+    let options = PairingOptions::new(UidVersion::V1).with_kickoff_tolerance(Duration::minutes(15));
+    let tolerant = pair_markets_with(records(), options).expect("pairing");
+    assert_eq!(tolerant.pairs.len(), 1);
+    let pair = &tolerant.pairs[0];
+    assert_eq!(pair.kickoff_gap(), Duration::minutes(7));
+    assert_eq!(pair.right.record.key.uid, records()[1].1.key.uid);
+    assert_eq!(tolerant.unmatched.len(), 1, "the 90-minute-later listing is another event");
+    assert!(tolerant.ambiguous.is_empty());
+}
+
+#[test]
+fn two_candidate_kickoffs_inside_the_window_are_reported_as_ambiguous() {
+    let records = vec![
+        keyed(identifier("sx", "Dodgers vs Giants", "moneyline", "home", None)),
+        keyed(kicking_off(identifier("azuro", "Dodgers vs Giants", "moneyline", "away", None), -10)),
+        keyed(kicking_off(identifier("azuro", "Dodgers vs Giants", "moneyline", "away", None), 10)),
+    ];
+    let options = PairingOptions::new(UidVersion::V1).with_kickoff_tolerance(Duration::minutes(15));
+
+    let result = pair_markets_with(records, options).expect("pairing");
+
+    assert!(result.pairs.is_empty());
+    assert!(result.unmatched.is_empty());
+    assert_eq!(result.ambiguous.len(), 3);
+    assert_eq!(result.ambiguous[0].record.payload, "sx:home");
+    assert_eq!(result.ambiguous[0].candidate_kickoffs.len(), 2);
+    assert_eq!(result.ambiguous[1].candidate_kickoffs.len(), 1);
+    assert_eq!(result.match_ratio(3), 0.0);
+}
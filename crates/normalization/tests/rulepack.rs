@@ -0,0 +1,60 @@
+use chrono::TimeZone;
+use normalization::{MarketIdentifier, Overtime, Postponement, RuleConflict, Rulepack};
+
+fn rulepack() -> Rulepack {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../../config/rulepacks/settlement.yml");
+    Rulepack::load(path).expect("rulepack")
+}
+
+fn identifier(operator: &str, sport: &str, market_type: &str) -> MarketIdentifier {
+    MarketIdentifier {
+        operator: operator.into(),
+        sport: sport.into(),
+        league: "league".into(),
+        event: "Home vs Away".into(),
+        market_type: market_type.into(),
+        outcome: "home".into(),
+        event_timestamp: chrono::Utc.with_ymd_and_hms(2024, 11, 5, 20, 0, 0).unwrap(),
+        variant: None,
+        ladder: None,
+    }
+}
+
+#[test]
+fn config_rulepack_flags_settlement_conflicts() {
+    let rulepack = rulepack();
+    assert_eq!(rulepack.compatible_markets(&identifier("sx", "Soccer", "match_winner"), &identifier("azuro", "Soccer", "match_winner")), Ok(()));
+    assert_eq!(rulepack.compatible_markets(&identifier("sx", "Soccer", "spread"), &identifier("azuro", "soccer", "spread")), Ok(()));
+
+    let basketball = rulepack
+        .compatible_markets(&identifier("sx", "Basketball", "total"), &identifier("azuro", "Basketball", "total"))
+        .unwrap_err();
+    assert_eq!(basketball, vec![RuleConflict::Postponement { left: Postponement::VoidAfterHours(24), right: Postponement::Void }]);
+
+    let hockey = rulepack
+        .compatible_markets(&identifier("sx", "Ice Hockey", "match_winner"), &identifier("azuro", "Ice Hockey", "match_winner"))
+        .unwrap_err();
+    assert_eq!(hockey, vec![RuleConflict::Overtime { left: Overtime::Included, right: Overtime::Excluded }]);
+}
+
+#[test]
+fn unknown_legs_are_reported_as_missing_rules() {
+    let conflicts = rulepack()
+        .compatible_markets(&identifier("sx", "Tennis", "match_winner"), &identifier("azuro", "Tennis", "match_winner"))
+        .unwrap_err();
+    assert_eq!(conflicts.len(), 2);
+    assert!(matches!(&conflicts[0], RuleConflict::MissingRules { operator, sport, .. } if operator == "sx" && sport == "tennis"));
+}
+
+#[test]
+fn json_rulepacks_use_the_same_schema() {
+    let json = r#"{"version": 1, "rules": [
+        {"operator": "sx", "sport": "soccer", "market_type": "total", "overtime": "excluded", "period": "first_half", "postponement": {"void_after_hours": 48}}
+    ]}"#;
+    let rulepack = Rulepack::from_json_str(json).expect("json rulepack");
+    let rules = rulepack.lookup("SX", "Soccer", "total").expect("rules");
+    assert_eq!(rules.postponement, Postponement::VoidAfterHours(48));
+    assert!(rulepack.lookup("sx", "soccer", "spread").is_none());
+
+    assert!(Rulepack::from_json_str(r#"{"version": 2, "rules": []}"#).is_err());
+}
@@ -0,0 +1,78 @@
+use std::{fs, path::Path, time::{Duration, Instant}};
+
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use crate::{Result, SxClientError, SxMetadata};
+
+/// Static SX provider settings as declared in `config/providers/sx.yml`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SxProviderConfig { pub odds_slippage: SlippageClamp, pub odds_ladder: OddsLadder, pub heartbeat: HeartbeatConfig, pub betting_delay_ms: u64 }
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct SlippageClamp { pub clamp_min: Decimal, pub clamp_max: Decimal }
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct OddsLadder { pub min: Decimal, pub max: Decimal, pub step: Decimal }
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct HeartbeatConfig { pub interval_ms: u64, #[serde(default)] pub grace_missed_beats: u32 }
+
+#[derive(Deserialize)]
+struct ProvidersFile { providers: Providers }
+#[derive(Deserialize)]
+struct Providers { sx: SxProviderConfig }
+
+impl SxProviderConfig {
+    pub fn from_yaml_str(content: &str) -> Result<Self> {
+        let file: ProvidersFile = serde_yaml::from_str(content).map_err(|err| SxClientError::Config(err.to_string()))?;
+        Ok(file.providers.sx)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|err| SxClientError::Config(format!("{}: {err}", path.display())))?;
+        Self::from_yaml_str(&content)
+    }
+
+    /// Heartbeat budget: one interval plus the tolerated missed beats.
+    pub fn heartbeat(&self) -> Duration {
+        let beats = u64::from(self.heartbeat.grace_missed_beats) + 1;
+        Duration::from_millis(self.heartbeat.interval_ms.saturating_mul(beats))
+    }
+
+    pub fn to_metadata(&self, fetched_at: Instant) -> SxMetadata {
+        SxMetadata {
+            odds_ladder_step: self.odds_ladder.step,
+            odds_ladder_min: self.odds_ladder.min,
+            odds_ladder_max: self.odds_ladder.max,
+            betting_delay: Duration::from_millis(self.betting_delay_ms),
+            heartbeat: self.heartbeat(),
+            min_odds_slippage: self.odds_slippage.clamp_min,
+            max_odds_slippage: self.odds_slippage.clamp_max,
+            fetched_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_repository_provider_file() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../config/providers/sx.yml");
+        let config = SxProviderConfig::load(path).expect("sx provider config");
+        let meta = config.to_metadata(Instant::now());
+        assert_eq!(meta.odds_ladder_min, Decimal::new(101, 2));
+        assert_eq!(meta.odds_ladder_max, Decimal::new(20, 0));
+        assert_eq!(meta.odds_ladder_step, Decimal::new(1, 2));
+        assert_eq!(meta.max_odds_slippage, Decimal::new(3, 2));
+        assert!(meta.min_odds_slippage.is_zero());
+        assert_eq!(meta.heartbeat, Duration::from_millis(10_000));
+        assert_eq!(meta.betting_delay, Duration::from_millis(1_000));
+    }
+
+    #[test]
+    fn rejects_missing_ladder() {
+        let err = SxProviderConfig::from_yaml_str("providers:\n  sx:\n    betting_delay_ms: 1000\n").expect_err("missing ladder");
+        assert_eq!(err.code(), "E-SX-CONFIG");
+    }
+}
@@ -1,9 +1,22 @@
 use std::{sync::Arc, time::{Duration, Instant}};
 
 use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::{sync::RwLock, time};
 
+use orders::{Claim, OrderTerms, Submission};
+
+pub mod config;
+pub mod orders;
+pub mod replay;
+pub mod throttle;
+
+pub use config::SxProviderConfig;
+pub use orders::{ClientOrderId, OrderEntry, OrderLedger};
+pub use rust_decimal::Decimal;
+pub use throttle::{BucketConfig, CircuitBreaker, CircuitBreakerConfig, CircuitState, OperationClass, RateLimitConfig, RateLimiter};
+
 pub type Result<T> = std::result::Result<T, SxClientError>;
 
 #[derive(Clone)]
@@ -13,17 +26,47 @@ pub struct SxClient {
     quotes: Arc<dyn QuoteSource>,
     executor: Arc<dyn OrderExecutor>,
     cached_metadata: Arc<RwLock<Option<SxMetadata>>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    breaker: Option<Arc<CircuitBreaker>>,
+    orders: Arc<OrderLedger>,
 }
 
 impl SxClient {
     pub fn new(ttl: Duration, metadata: Arc<dyn MetadataProvider>, quotes: Arc<dyn QuoteSource>, executor: Arc<dyn OrderExecutor>) -> Self {
-        Self { ttl, metadata, quotes, executor, cached_metadata: Arc::new(RwLock::new(None)) }
+        Self { ttl, metadata, quotes, executor, cached_metadata: Arc::new(RwLock::new(None)), rate_limiter: None, breaker: None, orders: Arc::new(OrderLedger::default()) }
+    }
+
+    pub fn with_rate_limits(mut self, config: RateLimitConfig) -> Self {
+        self.rate_limiter = Some(Arc::new(RateLimiter::new(config)));
+        self
+    }
+
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.breaker = Some(Arc::new(CircuitBreaker::new(config)));
+        self
+    }
+
+    /// Keeps completed orders for `ttl` to answer retries; defaults to `orders::DEFAULT_COMPLETED_TTL`.
+    pub fn with_completed_order_ttl(mut self, ttl: Duration) -> Self {
+        self.orders = Arc::new(OrderLedger::with_completed_ttl(ttl));
+        self
+    }
+
+    /// Ledger of client order ids submitted through this client.
+    pub fn orders(&self) -> &OrderLedger {
+        &self.orders
+    }
+
+    /// Breaker state guarding order submission, `None` when no breaker is configured.
+    pub fn circuit_state(&self) -> Option<CircuitState> {
+        self.breaker.as_ref().map(|breaker| breaker.state())
     }
 
     pub async fn get_best_quote(&self, request: QuoteRequest) -> Result<Quote> {
         let meta = self.load_metadata().await?;
+        self.acquire(OperationClass::Quotes)?;
         let mut quote = self.quotes.best_quote(&request).await?;
-        quote.odds = align_to_ladder(quote.odds, meta.odds_ladder_step)?;
+        quote.odds = align_to_ladder(quote.odds, &meta, LadderRounding::for_side(&quote.side)?)?;
         Ok(quote)
     }
 
@@ -32,25 +75,74 @@ impl SxClient {
         if request.odds_slippage > meta.max_odds_slippage {
             return Err(SxClientError::SlippageExceeded { requested: request.odds_slippage, max: meta.max_odds_slippage });
         }
-        let prepared = PreparedOrder { market_uid: request.market_uid.clone(), side: request.side.clone(), odds: align_to_ladder(request.odds, meta.odds_ladder_step)?, stake: request.stake, odds_slippage: request.odds_slippage, heartbeat: meta.heartbeat, betting_delay: meta.betting_delay };
+        if request.odds_slippage < meta.min_odds_slippage {
+            return Err(SxClientError::SlippageBelowMin { requested: request.odds_slippage, min: meta.min_odds_slippage });
+        }
+        let odds = align_to_ladder(request.odds, &meta, LadderRounding::for_side(&request.side)?)?;
+        let id = request.client_order_id.clone();
+        let prepared = PreparedOrder { client_order_id: id.clone(), market_uid: request.market_uid.clone(), side: request.side.clone(), odds, stake: request.stake, odds_slippage: request.odds_slippage, heartbeat: meta.heartbeat, betting_delay: meta.betting_delay };
         let total_timeout = meta
             .betting_delay
             .checked_add(meta.heartbeat)
             .unwrap_or(Duration::MAX);
-        let response = match time::timeout(total_timeout, self.executor.submit(prepared)).await {
-            Ok(res) => res?,
-            Err(_) => return Err(SxClientError::HeartbeatTimeout),
+        let retry = match self.orders.claim(&id, OrderTerms::of(&prepared)) {
+            Claim::Done(execution) => return Ok(execution),
+            Claim::Busy => return Err(SxClientError::OrderInFlight(id)),
+            Claim::Mismatch => return Err(SxClientError::OrderMismatch(id)),
+            Claim::Retry => true,
+            Claim::Fresh => false,
         };
-        let filled: f64 = response.fills.iter().map(|f| f.filled_stake).sum();
-        let remaining = (request.stake - filled).max(0.0);
-        let status = if remaining <= f64::EPSILON && matches!(response.status, OrderStatus::Accepted) {
+        if let Err(err) = self.admit_order() {
+            if retry {
+                self.orders.mark_unresolved(&id);
+            } else {
+                self.orders.release(&id);
+            }
+            return Err(err);
+        }
+        let submission = Submission::new(&self.orders, &id);
+        let outcome = match time::timeout(total_timeout, self.execute(prepared, retry)).await {
+            Ok(res) => res,
+            Err(_) => Err(SxClientError::HeartbeatTimeout),
+        };
+        if let Some(breaker) = &self.breaker {
+            match &outcome {
+                Ok(_) => breaker.record_success(),
+                Err(err) if err.is_venue_failure() => breaker.record_failure(),
+                Err(_) => breaker.release_probe(),
+            }
+        }
+        let response = outcome?;
+        let filled: Decimal = response.fills.iter().map(|f| f.filled_stake).sum();
+        let remaining = (request.stake - filled).max(Decimal::ZERO);
+        let status = if remaining.is_zero() && matches!(response.status, OrderStatus::Accepted) {
             OrderStatus::Accepted
-        } else if filled > 0.0 {
+        } else if filled > Decimal::ZERO {
             OrderStatus::PartiallyAccepted
         } else {
             OrderStatus::Void
         };
-        Ok(BetExecution { status, fills: response.fills, requested_stake: request.stake, remaining_stake: remaining })
+        let execution = BetExecution { client_order_id: id.clone(), status, fills: response.fills, requested_stake: request.stake, remaining_stake: remaining };
+        submission.complete(execution.clone());
+        Ok(execution)
+    }
+
+    /// Retries first ask the executor whether the order already exists before resubmitting it.
+    async fn execute(&self, prepared: PreparedOrder, retry: bool) -> Result<OrderResponse> {
+        if retry {
+            if let Some(existing) = self.executor.lookup(&prepared.client_order_id).await? {
+                return Ok(existing);
+            }
+        }
+        self.executor.submit(prepared).await
+    }
+
+    fn admit_order(&self) -> Result<()> {
+        self.acquire(OperationClass::Orders)?;
+        if let Some(breaker) = &self.breaker {
+            breaker.before_call()?;
+        }
+        Ok(())
     }
 
     async fn load_metadata(&self) -> Result<SxMetadata> {
@@ -60,6 +152,7 @@ impl SxClient {
             }
         }
 
+        self.acquire(OperationClass::Metadata)?;
         let fresh = self.metadata.latest().await?;
         self.ensure_metadata(&fresh)?;
 
@@ -73,14 +166,27 @@ impl SxClient {
         Ok(fresh)
     }
 
+    fn acquire(&self, class: OperationClass) -> Result<()> {
+        match &self.rate_limiter {
+            Some(limiter) => limiter.try_acquire(class),
+            None => Ok(()),
+        }
+    }
+
     fn ensure_metadata(&self, metadata: &SxMetadata) -> Result<()> {
         let age = Instant::now().saturating_duration_since(metadata.fetched_at);
         if age > self.ttl {
             return Err(SxClientError::MetadataStale { age });
         }
-        if metadata.odds_ladder_step <= 0.0 {
+        if metadata.odds_ladder_step <= Decimal::ZERO {
             return Err(SxClientError::InvalidMetadata("odds_ladder_step".into()));
         }
+        if metadata.odds_ladder_min <= Decimal::ONE || metadata.odds_ladder_max <= metadata.odds_ladder_min {
+            return Err(SxClientError::InvalidMetadata("odds_ladder bounds".into()));
+        }
+        if metadata.min_odds_slippage < Decimal::ZERO || metadata.max_odds_slippage < metadata.min_odds_slippage {
+            return Err(SxClientError::InvalidMetadata("odds_slippage clamp".into()));
+        }
         Ok(())
     }
 }
@@ -90,34 +196,51 @@ pub trait MetadataProvider: Send + Sync { async fn latest(&self) -> Result<SxMet
 #[async_trait]
 pub trait QuoteSource: Send + Sync { async fn best_quote(&self, request: &QuoteRequest) -> Result<Quote>; }
 #[async_trait]
-pub trait OrderExecutor: Send + Sync { async fn submit(&self, order: PreparedOrder) -> Result<OrderResponse>; }
-
-#[derive(Debug, Clone)]
-pub struct QuoteRequest { pub market_uid: String, pub side: String, pub stake: f64 }
-#[derive(Debug, Clone)]
-pub struct Quote { pub market_uid: String, pub side: String, pub odds: f64, pub available_stake: f64 }
-#[derive(Debug, Clone)]
-pub struct BetRequest { pub market_uid: String, pub side: String, pub odds: f64, pub stake: f64, pub odds_slippage: f64 }
-#[derive(Debug, Clone)]
-pub struct BetExecution { pub status: OrderStatus, pub fills: Vec<Fill>, pub requested_stake: f64, pub remaining_stake: f64 }
-#[derive(Debug, Clone, PartialEq)]
-pub struct Fill { pub fill_id: String, pub filled_stake: f64, pub odds: f64, pub accepted_at: Instant }
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub trait OrderExecutor: Send + Sync {
+    async fn submit(&self, order: PreparedOrder) -> Result<OrderResponse>;
+    /// Looks up an order by client id; `Ok(None)` means the venue never received it.
+    async fn lookup(&self, client_order_id: &ClientOrderId) -> Result<Option<OrderResponse>> {
+        Err(SxClientError::OrderUnreconciled(client_order_id.clone()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteRequest { pub market_uid: String, pub side: String, pub stake: Decimal }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Quote { pub market_uid: String, pub side: String, pub odds: Decimal, pub available_stake: Decimal }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BetRequest { pub client_order_id: ClientOrderId, pub market_uid: String, pub side: String, pub odds: Decimal, pub stake: Decimal, pub odds_slippage: Decimal }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BetExecution { pub client_order_id: ClientOrderId, pub status: OrderStatus, pub fills: Vec<Fill>, pub requested_stake: Decimal, pub remaining_stake: Decimal }
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Fill { pub fill_id: String, pub filled_stake: Decimal, pub odds: Decimal, #[serde(with = "replay::instant_age")] pub accepted_at: Instant }
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OrderStatus { Accepted, PartiallyAccepted, Void }
-#[derive(Debug, Clone)]
-pub struct SxMetadata { pub odds_ladder_step: f64, pub betting_delay: Duration, pub heartbeat: Duration, pub max_odds_slippage: f64, pub fetched_at: Instant }
-#[derive(Debug, Clone)]
-pub struct PreparedOrder { pub market_uid: String, pub side: String, pub odds: f64, pub stake: f64, pub odds_slippage: f64, pub heartbeat: Duration, pub betting_delay: Duration }
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SxMetadata { pub odds_ladder_step: Decimal, pub odds_ladder_min: Decimal, pub odds_ladder_max: Decimal, pub betting_delay: Duration, pub heartbeat: Duration, pub min_odds_slippage: Decimal, pub max_odds_slippage: Decimal, #[serde(with = "replay::instant_age")] pub fetched_at: Instant }
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreparedOrder { pub client_order_id: ClientOrderId, pub market_uid: String, pub side: String, pub odds: Decimal, pub stake: Decimal, pub odds_slippage: Decimal, pub heartbeat: Duration, pub betting_delay: Duration }
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OrderResponse { pub status: OrderStatus, pub fills: Vec<Fill> }
 
-#[derive(Debug, Error)]
+#[derive(Debug, Error, Serialize, Deserialize)]
 pub enum SxClientError {
     #[error("metadata stale after {age:?}")] MetadataStale { age: Duration },
     #[error("invalid metadata: {0}")] InvalidMetadata(String),
-    #[error("requested slippage {requested} > max {max}")] SlippageExceeded { requested: f64, max: f64 },
-    #[error("odds {odds} incompatible with ladder step {step}")] OddsOutOfLadder { odds: f64, step: f64 },
+    #[error("requested slippage {requested} > max {max}")] SlippageExceeded { requested: Decimal, max: Decimal },
+    #[error("requested slippage {requested} < min {min}")] SlippageBelowMin { requested: Decimal, min: Decimal },
+    #[error("odds {odds} incompatible with ladder step {step}")] OddsOutOfLadder { odds: Decimal, step: Decimal },
+    #[error("odds {odds} outside ladder bounds [{min}, {max}]")] OddsOutOfBounds { odds: Decimal, min: Decimal, max: Decimal },
+    #[error("unknown side {0:?}, expected back or lay")] UnknownSide(String),
     #[error("heartbeat timeout")] HeartbeatTimeout,
+    #[error("invalid provider config: {0}")] Config(String),
+    #[error("{} rate limit reached, retry after {retry_after:?}", class.as_str())] RateLimited { class: OperationClass, retry_after: Duration },
+    #[error("circuit breaker open, retry after {retry_after:?}")] CircuitOpen { retry_after: Duration },
+    #[error("executor error: {0}")] Executor(String),
+    #[error("order {0} is already being submitted")] OrderInFlight(ClientOrderId),
+    #[error("order {0} could not be reconciled with the venue")] OrderUnreconciled(ClientOrderId),
+    #[error("order {0} was issued with a different market, side, stake or odds")] OrderMismatch(ClientOrderId),
+    #[error("cassette error: {0}")] Cassette(String),
 }
 
 impl SxClientError {
@@ -125,26 +248,67 @@ impl SxClientError {
         match self {
             SxClientError::MetadataStale { .. } => "E-SX-METADATA-STALE",
             SxClientError::InvalidMetadata(_) => "E-SX-METADATA-INVALID",
-            SxClientError::SlippageExceeded { .. } => "E-SX-ODDS-SLIPPAGE",
-            SxClientError::OddsOutOfLadder { .. } => "E-SX-ODDS-LADDER",
+            SxClientError::SlippageExceeded { .. } | SxClientError::SlippageBelowMin { .. } => "E-SX-ODDS-SLIPPAGE",
+            SxClientError::OddsOutOfLadder { .. } | SxClientError::OddsOutOfBounds { .. } => "E-SX-ODDS-LADDER",
+            SxClientError::UnknownSide(_) => "E-SX-SIDE-UNKNOWN",
             SxClientError::HeartbeatTimeout => "E-SX-PARTIAL-TIMEOUT",
+            SxClientError::Config(_) => "E-SX-CONFIG",
+            SxClientError::RateLimited { .. } => "E-SX-RATE-LIMITED",
+            SxClientError::CircuitOpen { .. } => "E-SX-CIRCUIT-OPEN",
+            SxClientError::Executor(_) => "E-SX-EXECUTOR",
+            SxClientError::OrderInFlight(_) => "E-SX-ORDER-IN-FLIGHT",
+            SxClientError::OrderUnreconciled(_) => "E-SX-ORDER-UNRECONCILED",
+            SxClientError::OrderMismatch(_) => "E-SX-ORDER-MISMATCH",
+            SxClientError::Cassette(_) => "E-SX-CASSETTE",
         }
     }
+
+    /// Transport and venue errors, the only ones the circuit breaker counts.
+    pub fn is_venue_failure(&self) -> bool {
+        matches!(self, SxClientError::Executor(_) | SxClientError::HeartbeatTimeout)
+    }
 }
 
-fn align_to_ladder(odds: f64, step: f64) -> Result<f64> {
-    if step <= 0.0 {
+/// Direction used when snapping odds onto the ladder: back rounds down, lay rounds up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LadderRounding { Down, Up }
+
+impl LadderRounding {
+    /// Backing rounds down and laying rounds up; any other side is rejected instead of guessing a direction.
+    pub fn for_side(side: &str) -> Result<Self> {
+        match side.trim().to_ascii_lowercase().as_str() {
+            "back" => Ok(LadderRounding::Down),
+            "lay" => Ok(LadderRounding::Up),
+            _ => Err(SxClientError::UnknownSide(side.to_string())),
+        }
+    }
+}
+
+fn align_to_ladder(odds: Decimal, meta: &SxMetadata, rounding: LadderRounding) -> Result<Decimal> {
+    let step = meta.odds_ladder_step;
+    if step <= Decimal::ZERO {
         return Err(SxClientError::InvalidMetadata("odds_ladder_step".into()));
     }
-    if !odds.is_finite() {
-        return Err(SxClientError::OddsOutOfLadder { odds, step });
+    let (min, max) = (meta.odds_ladder_min, meta.odds_ladder_max);
+    if odds < min || odds > max {
+        return Err(SxClientError::OddsOutOfBounds { odds, min, max });
     }
-    Ok((odds / step).round() * step)
+    let ticks = odds.checked_div(step).ok_or(SxClientError::OddsOutOfLadder { odds, step })?;
+    let ticks = match rounding {
+        LadderRounding::Down => ticks.floor(),
+        LadderRounding::Up => ticks.ceil(),
+    };
+    let aligned = (ticks * step).normalize();
+    if aligned < min || aligned > max {
+        return Err(SxClientError::OddsOutOfBounds { odds: aligned, min, max });
+    }
+    Ok(aligned)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::str::FromStr;
     use std::sync::{Arc, Mutex};
     use std::sync::atomic::{AtomicUsize, Ordering};
 
@@ -216,12 +380,58 @@ mod tests {
         }
     }
 
+    #[derive(Clone, Default)]
+    struct FailingExecutor(Arc<AtomicUsize>);
+    #[async_trait]
+    impl OrderExecutor for FailingExecutor {
+        async fn submit(&self, _order: PreparedOrder) -> Result<OrderResponse> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Err(SxClientError::Executor("connection reset".into()))
+        }
+    }
+
+    /// Venue stand-in that books every order but drops the first `drops` responses.
+    #[derive(Clone, Default)]
+    struct DroppingExecutor {
+        drops: Arc<AtomicUsize>,
+        submissions: Arc<AtomicUsize>,
+        book: Arc<Mutex<std::collections::HashMap<ClientOrderId, OrderResponse>>>,
+    }
+
+    #[async_trait]
+    impl OrderExecutor for DroppingExecutor {
+        async fn submit(&self, order: PreparedOrder) -> Result<OrderResponse> {
+            self.submissions.fetch_add(1, Ordering::SeqCst);
+            let response = OrderResponse {
+                status: OrderStatus::Accepted,
+                fills: vec![Fill { fill_id: format!("fill-{}", order.client_order_id), filled_stake: order.stake, odds: order.odds, accepted_at: Instant::now() }],
+            };
+            self.book.lock().expect("book").insert(order.client_order_id.clone(), response.clone());
+            if self.drops.load(Ordering::SeqCst) > 0 {
+                self.drops.fetch_sub(1, Ordering::SeqCst);
+                return Err(SxClientError::Executor("response dropped".into()));
+            }
+            Ok(response)
+        }
+
+        async fn lookup(&self, client_order_id: &ClientOrderId) -> Result<Option<OrderResponse>> {
+            Ok(self.book.lock().expect("book").get(client_order_id).cloned())
+        }
+    }
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).expect("valid decimal")
+    }
+
     fn base_metadata() -> SxMetadata {
         SxMetadata {
-            odds_ladder_step: 0.05,
+            odds_ladder_step: dec("0.05"),
+            odds_ladder_min: dec("1.05"),
+            odds_ladder_max: dec("20.0"),
             betting_delay: Duration::from_secs(5),
             heartbeat: Duration::from_secs(30),
-            max_odds_slippage: 0.03,
+            min_odds_slippage: dec("0.0"),
+            max_odds_slippage: dec("0.03"),
             fetched_at: Instant::now(),
         }
     }
@@ -238,25 +448,126 @@ mod tests {
     async fn get_best_quote_aligns_to_ladder() {
         let mut metadata = base_metadata();
         metadata.heartbeat = Duration::from_secs(10);
-        metadata.max_odds_slippage = 0.02;
-        let quote = Quote { market_uid: "m1".into(), side: "back".into(), odds: 1.934, available_stake: 100.0 };
+        metadata.max_odds_slippage = dec("0.02");
+        let quote = Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.934"), available_stake: dec("100.0") };
+        let client = client(metadata, Arc::new(StaticQuote(quote)), Arc::new(StaticExecutor(OrderResponse { status: OrderStatus::Accepted, fills: vec![] })));
+        let quote = client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "back".into(), stake: dec("50.0") }).await.expect("quote");
+        assert_eq!(quote.odds, dec("1.9"), "backing rounds down");
+    }
+
+    #[tokio::test]
+    async fn get_best_quote_rounds_lay_up() {
+        let quote = Quote { market_uid: "m1".into(), side: "lay".into(), odds: dec("1.906"), available_stake: dec("100.0") };
+        let client = client(base_metadata(), Arc::new(StaticQuote(quote)), Arc::new(StaticExecutor(OrderResponse { status: OrderStatus::Accepted, fills: vec![] })));
+        let quote = client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "lay".into(), stake: dec("50.0") }).await.expect("quote");
+        assert_eq!(quote.odds, dec("1.95"), "laying rounds up");
+    }
+
+    #[tokio::test]
+    async fn get_best_quote_keeps_odds_already_on_ladder() {
+        let quote = Quote { market_uid: "m1".into(), side: "lay".into(), odds: dec("1.9"), available_stake: dec("100.0") };
+        let client = client(base_metadata(), Arc::new(StaticQuote(quote)), Arc::new(StaticExecutor(OrderResponse { status: OrderStatus::Accepted, fills: vec![] })));
+        let quote = client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "lay".into(), stake: dec("50.0") }).await.expect("quote");
+        assert_eq!(quote.odds, dec("1.9"));
+    }
+
+    #[tokio::test]
+    async fn get_best_quote_rejects_odds_outside_ladder_bounds() {
+        let quote = Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("25.0"), available_stake: dec("100.0") };
+        let client = client(base_metadata(), Arc::new(StaticQuote(quote)), Arc::new(StaticExecutor(OrderResponse { status: OrderStatus::Accepted, fills: vec![] })));
+        let err = client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "back".into(), stake: dec("50.0") }).await.expect_err("odds above ladder max");
+        assert!(matches!(err, SxClientError::OddsOutOfBounds { .. }));
+        assert_eq!(err.code(), "E-SX-ODDS-LADDER");
+    }
+
+    #[tokio::test]
+    async fn get_best_quote_aligns_exactly_on_fine_ladder() {
+        let mut metadata = base_metadata();
+        metadata.odds_ladder_step = dec("0.01");
+        let quote = Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9500000001"), available_stake: dec("100") };
         let client = client(metadata, Arc::new(StaticQuote(quote)), Arc::new(StaticExecutor(OrderResponse { status: OrderStatus::Accepted, fills: vec![] })));
-        let quote = client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "back".into(), stake: 50.0 }).await.expect("quote");
-        assert!((quote.odds - 1.95).abs() < 1e-9);
+        let quote = client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "back".into(), stake: dec("10") }).await.expect("quote");
+        assert_eq!(quote.odds.to_string(), "1.95");
+    }
+
+    #[tokio::test]
+    async fn unknown_side_is_rejected_instead_of_rounded() {
+        let quote = Quote { market_uid: "m1".into(), side: "home".into(), odds: dec("1.934"), available_stake: dec("100.0") };
+        let client = client(base_metadata(), Arc::new(StaticQuote(quote)), Arc::new(StaticExecutor(OrderResponse { status: OrderStatus::Accepted, fills: vec![] })));
+        let err = client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "home".into(), stake: dec("50.0") }).await.expect_err("unknown side");
+        assert_eq!(err.code(), "E-SX-SIDE-UNKNOWN");
+        let err = client
+            .place_bet(BetRequest { client_order_id: ClientOrderId::generate(), market_uid: "m1".into(), side: "home".into(), odds: dec("1.934"), stake: dec("10.0"), odds_slippage: dec("0.01") })
+            .await
+            .expect_err("unknown side");
+        assert!(matches!(err, SxClientError::UnknownSide(side) if side == "home"));
+        assert_eq!(LadderRounding::for_side(" Back ").expect("back"), LadderRounding::Down);
+    }
+
+    #[tokio::test]
+    async fn place_bet_sums_fills_exactly() {
+        let fills = vec![
+            Fill { fill_id: "f1".into(), filled_stake: dec("0.1"), odds: dec("1.9"), accepted_at: Instant::now() },
+            Fill { fill_id: "f2".into(), filled_stake: dec("0.2"), odds: dec("1.9"), accepted_at: Instant::now() },
+        ];
+        let executor = StaticExecutor(OrderResponse { status: OrderStatus::Accepted, fills });
+        let client = client(base_metadata(), Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), available_stake: dec("0") })), Arc::new(executor));
+        let execution = client
+            .place_bet(BetRequest { client_order_id: ClientOrderId::generate(), market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), stake: dec("0.3"), odds_slippage: dec("0.01") })
+            .await
+            .expect("bet execution");
+        assert_eq!(execution.status, OrderStatus::Accepted);
+        assert!(execution.remaining_stake.is_zero());
+    }
+
+    #[tokio::test]
+    async fn place_bet_rejects_odds_below_ladder_min() {
+        let client = client(base_metadata(), Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), available_stake: dec("0.0") })), Arc::new(StaticExecutor(OrderResponse { status: OrderStatus::Accepted, fills: vec![] })));
+        let err = client
+            .place_bet(BetRequest { client_order_id: ClientOrderId::generate(), market_uid: "m1".into(), side: "back".into(), odds: dec("1.02"), stake: dec("10.0"), odds_slippage: dec("0.01") })
+            .await
+            .expect_err("odds below ladder min");
+        assert_eq!(err.code(), "E-SX-ODDS-LADDER");
+    }
+
+    #[tokio::test]
+    async fn place_bet_enforces_slippage_clamp() {
+        let mut metadata = base_metadata();
+        metadata.min_odds_slippage = dec("0.005");
+        let client = client(metadata, Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), available_stake: dec("0.0") })), Arc::new(StaticExecutor(OrderResponse { status: OrderStatus::Accepted, fills: vec![] })));
+        let below = client
+            .place_bet(BetRequest { client_order_id: ClientOrderId::generate(), market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), stake: dec("10.0"), odds_slippage: dec("0.001") })
+            .await
+            .expect_err("slippage below clamp");
+        assert!(matches!(below, SxClientError::SlippageBelowMin { .. }));
+        let above = client
+            .place_bet(BetRequest { client_order_id: ClientOrderId::generate(), market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), stake: dec("10.0"), odds_slippage: dec("0.05") })
+            .await
+            .expect_err("slippage above clamp");
+        assert_eq!(above.code(), "E-SX-ODDS-SLIPPAGE");
+    }
+
+    #[tokio::test]
+    async fn invalid_ladder_bounds_are_rejected() {
+        let mut metadata = base_metadata();
+        metadata.odds_ladder_max = dec("1.0");
+        let client = client(metadata, Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), available_stake: dec("0.0") })), Arc::new(StaticExecutor(OrderResponse { status: OrderStatus::Accepted, fills: vec![] })));
+        let err = client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "back".into(), stake: dec("10.0") }).await.expect_err("invalid bounds");
+        assert_eq!(err.code(), "E-SX-METADATA-INVALID");
     }
 
     #[tokio::test]
     async fn place_bet_marks_partial_fill() {
         let metadata = base_metadata();
-        let fills = vec![Fill { fill_id: "f1".into(), filled_stake: 60.0, odds: 1.92, accepted_at: Instant::now() }];
+        let fills = vec![Fill { fill_id: "f1".into(), filled_stake: dec("60.0"), odds: dec("1.92"), accepted_at: Instant::now() }];
         let executor = StaticExecutor(OrderResponse { status: OrderStatus::Accepted, fills: fills.clone() });
-        let client = client(metadata, Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: 1.9, available_stake: 0.0 })), Arc::new(executor));
+        let client = client(metadata, Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), available_stake: dec("0.0") })), Arc::new(executor));
         let execution = client
-            .place_bet(BetRequest { market_uid: "m1".into(), side: "back".into(), odds: 1.91, stake: 100.0, odds_slippage: 0.02 })
+            .place_bet(BetRequest { client_order_id: ClientOrderId::generate(), market_uid: "m1".into(), side: "back".into(), odds: dec("1.91"), stake: dec("100.0"), odds_slippage: dec("0.02") })
             .await
             .expect("bet execution");
         assert_eq!(execution.status, OrderStatus::PartiallyAccepted);
-        assert!((execution.remaining_stake - 40.0).abs() < f64::EPSILON);
+        assert_eq!(execution.remaining_stake, dec("40"));
         assert_eq!(execution.fills, fills);
     }
 
@@ -265,9 +576,9 @@ mod tests {
         let mut metadata = base_metadata();
         metadata.heartbeat = Duration::from_millis(20);
         metadata.betting_delay = Duration::from_millis(5);
-        let client = client(metadata, Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "lay".into(), odds: 1.9, available_stake: 0.0 })), Arc::new(SlowExecutor(Duration::from_millis(40))));
+        let client = client(metadata, Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "lay".into(), odds: dec("1.9"), available_stake: dec("0.0") })), Arc::new(SlowExecutor(Duration::from_millis(40))));
         let result = client
-            .place_bet(BetRequest { market_uid: "m1".into(), side: "lay".into(), odds: 1.9, stake: 10.0, odds_slippage: 0.01 })
+            .place_bet(BetRequest { client_order_id: ClientOrderId::generate(), market_uid: "m1".into(), side: "lay".into(), odds: dec("1.9"), stake: dec("10.0"), odds_slippage: dec("0.01") })
             .await;
         assert!(matches!(result, Err(SxClientError::HeartbeatTimeout)));
     }
@@ -277,15 +588,15 @@ mod tests {
         let mut metadata = base_metadata();
         metadata.heartbeat = Duration::from_millis(40);
         metadata.betting_delay = Duration::from_millis(60);
-        let fills = vec![Fill { fill_id: "f1".into(), filled_stake: 10.0, odds: 1.91, accepted_at: Instant::now() }];
+        let fills = vec![Fill { fill_id: "f1".into(), filled_stake: dec("10.0"), odds: dec("1.91"), accepted_at: Instant::now() }];
         let executor = DelayedExecutor { delay: Duration::from_millis(80), response: OrderResponse { status: OrderStatus::Accepted, fills: fills.clone() } };
-        let client = client(metadata, Arc::new(StaticQuote(Quote { market_uid: "m2".into(), side: "back".into(), odds: 2.0, available_stake: 50.0 })), Arc::new(executor));
+        let client = client(metadata, Arc::new(StaticQuote(Quote { market_uid: "m2".into(), side: "back".into(), odds: dec("2.0"), available_stake: dec("50.0") })), Arc::new(executor));
         let execution = client
-            .place_bet(BetRequest { market_uid: "m2".into(), side: "back".into(), odds: 1.95, stake: 10.0, odds_slippage: 0.02 })
+            .place_bet(BetRequest { client_order_id: ClientOrderId::generate(), market_uid: "m2".into(), side: "back".into(), odds: dec("1.95"), stake: dec("10.0"), odds_slippage: dec("0.02") })
             .await
             .expect("bet execution");
         assert_eq!(execution.status, OrderStatus::Accepted);
-        assert!((execution.remaining_stake).abs() < f64::EPSILON);
+        assert!(execution.remaining_stake.is_zero());
         assert_eq!(execution.fills, fills);
     }
 
@@ -293,14 +604,14 @@ mod tests {
     async fn metadata_stale_is_rejected() {
         let mut metadata = base_metadata();
         metadata.fetched_at = Instant::now() - Duration::from_secs(61);
-        let client = client(metadata, Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: 1.9, available_stake: 0.0 })), Arc::new(StaticExecutor(OrderResponse { status: OrderStatus::Accepted, fills: vec![] })));
-        let result = client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "back".into(), stake: 10.0 }).await;
+        let client = client(metadata, Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), available_stake: dec("0.0") })), Arc::new(StaticExecutor(OrderResponse { status: OrderStatus::Accepted, fills: vec![] })));
+        let result = client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "back".into(), stake: dec("10.0") }).await;
         assert!(matches!(result, Err(SxClientError::MetadataStale { .. })));
     }
 
     #[tokio::test]
     async fn metadata_is_cached_until_ttl_expires() {
-        let quote = Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: 1.9, available_stake: 50.0 }));
+        let quote = Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), available_stake: dec("50.0") }));
         let exec = Arc::new(StaticExecutor(OrderResponse { status: OrderStatus::Accepted, fills: vec![] }));
         let now = Instant::now();
         let snapshots = vec![
@@ -310,12 +621,188 @@ mod tests {
         let provider = SequenceMetadata::new(snapshots);
         let client = SxClient::new(Duration::from_millis(15), Arc::new(provider.clone()), quote.clone(), exec.clone());
 
-        client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "back".into(), stake: 10.0 }).await.expect("first quote");
-        client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "back".into(), stake: 10.0 }).await.expect("second quote");
+        client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "back".into(), stake: dec("10.0") }).await.expect("first quote");
+        client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "back".into(), stake: dec("10.0") }).await.expect("second quote");
         assert_eq!(provider.call_count(), 1, "metadata should be cached within TTL");
 
         time::sleep(Duration::from_millis(16)).await;
-        client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "back".into(), stake: 10.0 }).await.expect("third quote");
+        client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "back".into(), stake: dec("10.0") }).await.expect("third quote");
         assert_eq!(provider.call_count(), 2, "metadata should refresh after TTL");
     }
+
+    #[tokio::test]
+    async fn quotes_are_rate_limited_without_starving_orders() {
+        let limits = RateLimitConfig {
+            quotes: BucketConfig { capacity: 1, refill_per_sec: 0.0 },
+            ..RateLimitConfig::default()
+        };
+        let client = client(base_metadata(), Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), available_stake: dec("10") })), Arc::new(StaticExecutor(OrderResponse { status: OrderStatus::Accepted, fills: vec![] })))
+            .with_rate_limits(limits);
+        let request = QuoteRequest { market_uid: "m1".into(), side: "back".into(), stake: dec("10") };
+        client.get_best_quote(request.clone()).await.expect("first quote");
+        let err = client.get_best_quote(request).await.expect_err("quote bucket exhausted");
+        assert_eq!(err.code(), "E-SX-RATE-LIMITED");
+        client
+            .place_bet(BetRequest { client_order_id: ClientOrderId::generate(), market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), stake: dec("10"), odds_slippage: dec("0.01") })
+            .await
+            .expect("orders keep their own budget");
+    }
+
+    #[tokio::test]
+    async fn circuit_opens_after_consecutive_executor_errors() {
+        let executor = FailingExecutor::default();
+        let calls = executor.0.clone();
+        let client = client(base_metadata(), Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), available_stake: dec("10") })), Arc::new(executor))
+            .with_circuit_breaker(CircuitBreakerConfig { failure_threshold: 2, cooldown_ms: 60_000 });
+        assert_eq!(client.circuit_state(), Some(CircuitState::Closed));
+        let bet = BetRequest { client_order_id: ClientOrderId::generate(), market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), stake: dec("10"), odds_slippage: dec("0.01") };
+        for _ in 0..2 {
+            let err = client.place_bet(BetRequest { client_order_id: ClientOrderId::generate(), ..bet.clone() }).await.expect_err("executor failure");
+            assert_eq!(err.code(), "E-SX-EXECUTOR");
+        }
+        assert!(matches!(client.circuit_state(), Some(CircuitState::Open { .. })));
+        let err = client.place_bet(bet).await.expect_err("circuit open");
+        assert_eq!(err.code(), "E-SX-CIRCUIT-OPEN");
+        assert_eq!(calls.load(Ordering::SeqCst), 2, "open circuit must not reach the executor");
+    }
+
+    #[tokio::test]
+    async fn retry_after_dropped_response_reconciles_instead_of_resubmitting() {
+        let executor = DroppingExecutor::default();
+        executor.drops.store(1, Ordering::SeqCst);
+        let client = client(base_metadata(), Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), available_stake: dec("10") })), Arc::new(executor.clone()));
+        let bet = BetRequest { client_order_id: ClientOrderId::new("cid-1"), market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), stake: dec("10"), odds_slippage: dec("0.01") };
+
+        let err = client.place_bet(bet.clone()).await.expect_err("response dropped");
+        assert_eq!(err.code(), "E-SX-EXECUTOR");
+        assert!(matches!(client.orders().get(&bet.client_order_id), Some(OrderEntry::Unresolved)));
+
+        let execution = client.place_bet(bet.clone()).await.expect("reconciled execution");
+        assert_eq!(execution.status, OrderStatus::Accepted);
+        assert_eq!(execution.client_order_id, bet.client_order_id);
+        assert_eq!(executor.submissions.load(Ordering::SeqCst), 1, "retry must not create a second order");
+
+        let again = client.place_bet(bet).await.expect("completed order is replayed");
+        assert_eq!(again.fills, execution.fills);
+        assert_eq!(executor.submissions.load(Ordering::SeqCst), 1);
+        assert!(client.orders().in_flight().is_empty());
+    }
+
+    #[tokio::test]
+    async fn retry_resubmits_when_venue_never_received_order() {
+        #[derive(Clone, Default)]
+        struct LostExecutor { submissions: Arc<AtomicUsize> }
+        #[async_trait]
+        impl OrderExecutor for LostExecutor {
+            async fn submit(&self, order: PreparedOrder) -> Result<OrderResponse> {
+                if self.submissions.fetch_add(1, Ordering::SeqCst) == 0 {
+                    return Err(SxClientError::Executor("request lost".into()));
+                }
+                Ok(OrderResponse { status: OrderStatus::Accepted, fills: vec![Fill { fill_id: "f1".into(), filled_stake: order.stake, odds: order.odds, accepted_at: Instant::now() }] })
+            }
+
+            async fn lookup(&self, _client_order_id: &ClientOrderId) -> Result<Option<OrderResponse>> { Ok(None) }
+        }
+
+        let executor = LostExecutor::default();
+        let client = client(base_metadata(), Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), available_stake: dec("10") })), Arc::new(executor.clone()));
+        let bet = BetRequest { client_order_id: ClientOrderId::new("cid-2"), market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), stake: dec("10"), odds_slippage: dec("0.01") };
+        client.place_bet(bet.clone()).await.expect_err("request lost");
+        let execution = client.place_bet(bet).await.expect("resubmitted");
+        assert_eq!(execution.status, OrderStatus::Accepted);
+        assert_eq!(executor.submissions.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn retry_without_lookup_support_refuses_to_resubmit() {
+        let executor = FailingExecutor::default();
+        let calls = executor.0.clone();
+        let client = client(base_metadata(), Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), available_stake: dec("10") })), Arc::new(executor));
+        let bet = BetRequest { client_order_id: ClientOrderId::new("cid-3"), market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), stake: dec("10"), odds_slippage: dec("0.01") };
+        client.place_bet(bet.clone()).await.expect_err("executor failure");
+        let err = client.place_bet(bet).await.expect_err("cannot reconcile");
+        assert_eq!(err.code(), "E-SX-ORDER-UNRECONCILED");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn unreconciled_retries_do_not_trip_the_breaker() {
+        let executor = FailingExecutor::default();
+        let client = client(base_metadata(), Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), available_stake: dec("10") })), Arc::new(executor))
+            .with_circuit_breaker(CircuitBreakerConfig { failure_threshold: 2, cooldown_ms: 60_000 });
+        let bet = BetRequest { client_order_id: ClientOrderId::new("cid-5"), market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), stake: dec("10"), odds_slippage: dec("0.01") };
+        client.place_bet(bet.clone()).await.expect_err("executor failure");
+        for _ in 0..3 {
+            let err = client.place_bet(bet.clone()).await.expect_err("cannot reconcile");
+            assert_eq!(err.code(), "E-SX-ORDER-UNRECONCILED");
+        }
+        assert_eq!(client.circuit_state(), Some(CircuitState::Closed));
+        assert_eq!(client.breaker.as_ref().map(|breaker| breaker.consecutive_failures()), Some(1));
+    }
+
+    #[tokio::test]
+    async fn cancelled_submission_leaves_the_order_retryable() {
+        let executor = DelayedExecutor { delay: Duration::from_millis(200), response: OrderResponse { status: OrderStatus::Accepted, fills: vec![] } };
+        let client = client(base_metadata(), Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), available_stake: dec("10") })), Arc::new(executor));
+        let bet = BetRequest { client_order_id: ClientOrderId::new("cid-6"), market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), stake: dec("10"), odds_slippage: dec("0.01") };
+        time::timeout(Duration::from_millis(10), client.place_bet(bet.clone())).await.expect_err("caller gave up");
+        assert!(matches!(client.orders().get(&bet.client_order_id), Some(OrderEntry::Unresolved)));
+        let err = client.place_bet(bet).await.expect_err("retry reconciles instead of reporting in flight");
+        assert_eq!(err.code(), "E-SX-ORDER-UNRECONCILED");
+    }
+
+    #[tokio::test]
+    async fn evicted_orders_reconcile_instead_of_resubmitting() {
+        let executor = DroppingExecutor::default();
+        let client = client(base_metadata(), Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), available_stake: dec("10") })), Arc::new(executor.clone()))
+            .with_completed_order_ttl(Duration::from_millis(20));
+        let bet = BetRequest { client_order_id: ClientOrderId::new("cid-7"), market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), stake: dec("10"), odds_slippage: dec("0.01") };
+        let execution = client.place_bet(bet.clone()).await.expect("bet execution");
+        assert!(matches!(client.orders().get(&bet.client_order_id), Some(OrderEntry::Completed(_))));
+        time::sleep(Duration::from_millis(30)).await;
+        assert!(matches!(client.orders().get(&bet.client_order_id), Some(OrderEntry::Settled)));
+
+        let replayed = client.place_bet(bet.clone()).await.expect("reconciled execution");
+        assert_eq!(replayed.fills, execution.fills);
+        assert_eq!(executor.submissions.load(Ordering::SeqCst), 1, "evicted id must not create a second order");
+
+        let dropped = BetRequest { client_order_id: ClientOrderId::new("cid-8"), ..bet };
+        executor.drops.store(1, Ordering::SeqCst);
+        client.place_bet(dropped.clone()).await.expect_err("response dropped");
+        assert!(matches!(client.orders().forget(&dropped.client_order_id), Some(OrderEntry::Unresolved)));
+        client.place_bet(dropped).await.expect("reconciled execution");
+        assert_eq!(executor.submissions.load(Ordering::SeqCst), 2, "forgotten id must not create a second order");
+    }
+
+    #[tokio::test]
+    async fn retry_with_different_terms_is_rejected() {
+        let executor = DroppingExecutor::default();
+        let client = client(base_metadata(), Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), available_stake: dec("10") })), Arc::new(executor.clone()));
+        let bet = BetRequest { client_order_id: ClientOrderId::new("cid-9"), market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), stake: dec("10"), odds_slippage: dec("0.01") };
+        client.place_bet(bet.clone()).await.expect("bet execution");
+        for changed in [
+            BetRequest { market_uid: "m2".into(), ..bet.clone() },
+            BetRequest { side: "lay".into(), ..bet.clone() },
+            BetRequest { stake: dec("20"), ..bet.clone() },
+            BetRequest { odds: dec("2.1"), ..bet.clone() },
+        ] {
+            let err = client.place_bet(changed).await.expect_err("different terms");
+            assert_eq!(err.code(), "E-SX-ORDER-MISMATCH");
+        }
+        assert!(client.place_bet(BetRequest { side: " Back ".into(), ..bet }).await.is_ok());
+        assert_eq!(executor.submissions.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn concurrent_submission_with_same_id_is_rejected() {
+        let executor = DelayedExecutor { delay: Duration::from_millis(30), response: OrderResponse { status: OrderStatus::Accepted, fills: vec![] } };
+        let client = client(base_metadata(), Arc::new(StaticQuote(Quote { market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), available_stake: dec("10") })), Arc::new(executor));
+        let bet = BetRequest { client_order_id: ClientOrderId::new("cid-4"), market_uid: "m1".into(), side: "back".into(), odds: dec("1.9"), stake: dec("10"), odds_slippage: dec("0.01") };
+        let (first, second) = tokio::join!(client.place_bet(bet.clone()), async {
+            time::sleep(Duration::from_millis(5)).await;
+            client.place_bet(bet.clone()).await
+        });
+        assert!(first.is_ok());
+        assert_eq!(second.expect_err("in flight").code(), "E-SX-ORDER-IN-FLIGHT");
+    }
 }
@@ -0,0 +1,186 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{atomic::{AtomicU64, Ordering}, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BetExecution, Decimal, PreparedOrder};
+
+/// Client-generated idempotency key carried from `BetRequest` down to the executor.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ClientOrderId(String);
+
+static SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+impl ClientOrderId {
+    pub fn new(value: impl Into<String>) -> Self { Self(value.into()) }
+
+    /// Process-unique id built from wall-clock nanos, pid and a sequence counter.
+    pub fn generate() -> Self {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or_default();
+        let seq = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+        Self(format!("otter-{nanos:x}-{:x}-{seq:x}", std::process::id()))
+    }
+
+    pub fn as_str(&self) -> &str { &self.0 }
+}
+
+impl fmt::Display for ClientOrderId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str(&self.0) }
+}
+
+/// Lifecycle of a client order id as seen by this process.
+#[derive(Debug, Clone)]
+pub enum OrderEntry {
+    /// A submission is awaiting the executor right now.
+    Submitting,
+    /// A submission failed or timed out; the venue may or may not hold the order.
+    Unresolved,
+    Completed(BetExecution),
+    /// Completed past the TTL or forgotten: the execution is gone, so a retry reconciles through `lookup`.
+    Settled,
+}
+
+/// What a client order id was issued for; a retry must carry the same terms.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct OrderTerms { pub(crate) market_uid: String, pub(crate) side: String, pub(crate) stake: Decimal, pub(crate) odds: Decimal }
+
+impl OrderTerms {
+    pub(crate) fn of(order: &PreparedOrder) -> Self {
+        Self { market_uid: order.market_uid.clone(), side: order.side.trim().to_ascii_lowercase(), stake: order.stake, odds: order.odds }
+    }
+}
+
+pub(crate) enum Claim { Fresh, Retry, Busy, Done(BetExecution), Mismatch }
+
+/// How long a completed order is kept to answer retries with the same client order id.
+pub const DEFAULT_COMPLETED_TTL: Duration = Duration::from_secs(3600);
+
+#[derive(Debug)]
+struct Slot { entry: OrderEntry, terms: OrderTerms, at: Instant }
+
+/// Local store of client order ids; consulted before every submission. Completed entries older than
+/// the ledger TTL drop their execution and become `Settled` tombstones, which keep only the id and
+/// terms so the id is never submitted blind again.
+#[derive(Debug)]
+pub struct OrderLedger { entries: Mutex<HashMap<ClientOrderId, Slot>>, completed_ttl: Duration }
+
+impl Default for OrderLedger {
+    fn default() -> Self { Self::with_completed_ttl(DEFAULT_COMPLETED_TTL) }
+}
+
+impl OrderLedger {
+    pub fn with_completed_ttl(completed_ttl: Duration) -> Self {
+        Self { entries: Mutex::new(HashMap::new()), completed_ttl }
+    }
+
+    pub fn get(&self, id: &ClientOrderId) -> Option<OrderEntry> {
+        self.lock().get(id).map(|slot| slot.entry.clone())
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Claims `id` for submission unless it is in flight, completed, or was issued for other terms.
+    /// Ids the ledger has seen before are always claimed as retries, so `lookup` runs first.
+    pub(crate) fn claim(&self, id: &ClientOrderId, terms: OrderTerms) -> Claim {
+        let mut entries = self.lock();
+        let Some(slot) = entries.get_mut(id) else {
+            entries.insert(id.clone(), Slot { entry: OrderEntry::Submitting, terms, at: Instant::now() });
+            return Claim::Fresh;
+        };
+        if slot.terms != terms {
+            return Claim::Mismatch;
+        }
+        match &slot.entry {
+            OrderEntry::Unresolved | OrderEntry::Settled => {
+                slot.entry = OrderEntry::Submitting;
+                slot.at = Instant::now();
+                Claim::Retry
+            }
+            OrderEntry::Submitting => Claim::Busy,
+            OrderEntry::Completed(execution) => Claim::Done(execution.clone()),
+        }
+    }
+
+    pub(crate) fn complete(&self, id: &ClientOrderId, execution: BetExecution) {
+        self.set(id, OrderEntry::Completed(execution));
+    }
+
+    pub(crate) fn mark_unresolved(&self, id: &ClientOrderId) {
+        self.set(id, OrderEntry::Unresolved);
+    }
+
+    /// Drops a fresh claim that never reached the executor.
+    pub(crate) fn release(&self, id: &ClientOrderId) {
+        self.lock().remove(id);
+    }
+
+    /// Drops the stored execution of a settled id, e.g. once the fill has been persisted downstream.
+    /// The id stays behind as a `Settled` tombstone, so a later retry reconciles instead of resubmitting.
+    pub fn forget(&self, id: &ClientOrderId) -> Option<OrderEntry> {
+        let mut entries = self.lock();
+        let slot = entries.get_mut(id)?;
+        slot.at = Instant::now();
+        Some(std::mem::replace(&mut slot.entry, OrderEntry::Settled))
+    }
+
+    pub fn in_flight(&self) -> Vec<ClientOrderId> {
+        self.lock()
+            .iter()
+            .filter(|(_, slot)| matches!(slot.entry, OrderEntry::Submitting | OrderEntry::Unresolved))
+            .map(|(id, _)| id.clone())
+            .collect()
+    }
+
+    fn set(&self, id: &ClientOrderId, entry: OrderEntry) {
+        if let Some(slot) = self.lock().get_mut(id) {
+            slot.entry = entry;
+            slot.at = Instant::now();
+        }
+    }
+
+    /// Locks the entries after turning completed orders past the TTL into tombstones.
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<ClientOrderId, Slot>> {
+        let mut entries = self.entries.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let now = Instant::now();
+        for slot in entries.values_mut() {
+            if matches!(slot.entry, OrderEntry::Completed(_)) && now.saturating_duration_since(slot.at) >= self.completed_ttl {
+                slot.entry = OrderEntry::Settled;
+                slot.at = now;
+            }
+        }
+        entries
+    }
+}
+
+/// Claimed submission: marks the id `Unresolved` when dropped before `complete`, so a caller
+/// whose future is cancelled mid-submission can still retry with the same client order id.
+pub(crate) struct Submission<'a> { ledger: &'a OrderLedger, id: &'a ClientOrderId, done: bool }
+
+impl<'a> Submission<'a> {
+    pub(crate) fn new(ledger: &'a OrderLedger, id: &'a ClientOrderId) -> Self {
+        Self { ledger, id, done: false }
+    }
+
+    pub(crate) fn complete(mut self, execution: BetExecution) {
+        self.ledger.complete(self.id, execution);
+        self.done = true;
+    }
+}
+
+impl Drop for Submission<'_> {
+    fn drop(&mut self) {
+        if !self.done {
+            self.ledger.mark_unresolved(self.id);
+        }
+    }
+}
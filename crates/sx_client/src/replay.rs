@@ -0,0 +1,358 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::File,
+    io::{BufRead, BufReader, Write},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use tokio::time;
+use tracing::warn;
+
+use crate::{ClientOrderId, MetadataProvider, OrderExecutor, OrderResponse, PreparedOrder, Quote, QuoteRequest, QuoteSource, Result, SxClientError, SxMetadata};
+
+/// One recorded trait call, stored as a single JSON line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CassetteEntry { pub seq: u64, pub call: String, pub request: Value, pub response: Value, pub latency_us: u64 }
+
+const CALL_METADATA: &str = "metadata.latest";
+const CALL_QUOTE: &str = "quotes.best_quote";
+const CALL_SUBMIT: &str = "orders.submit";
+const CALL_LOOKUP: &str = "orders.lookup";
+
+/// Append-only JSON-lines sink shared by every recording wrapper of a session.
+pub struct CassetteWriter { sink: Mutex<(u64, Box<dyn Write + Send>)> }
+
+impl CassetteWriter {
+    pub fn new(sink: impl Write + Send + 'static) -> Self {
+        Self { sink: Mutex::new((0, Box::new(sink))) }
+    }
+
+    pub fn create(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::create(path).map_err(|err| cassette_error(format!("{}: {err}", path.display())))?;
+        Ok(Self::new(file))
+    }
+
+    fn record<Req: Serialize, Res: Serialize>(&self, call: &str, request: &Req, response: &Result<Res>, latency: Duration) -> Result<()> {
+        let mut guard = self.sink.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let entry = CassetteEntry {
+            seq: guard.0,
+            call: call.to_string(),
+            request: to_value(request)?,
+            response: to_value(response)?,
+            latency_us: u64::try_from(latency.as_micros()).unwrap_or(u64::MAX),
+        };
+        let line = serde_json::to_string(&entry).map_err(|err| cassette_error(err.to_string()))?;
+        writeln!(guard.1, "{line}").and_then(|_| guard.1.flush()).map_err(|err| cassette_error(err.to_string()))?;
+        guard.0 += 1;
+        Ok(())
+    }
+
+    /// Recording must never mask the live result (a placed bet reported as failed), so write errors are only logged.
+    fn record_or_warn<Req: Serialize, Res: Serialize>(&self, call: &str, request: &Req, response: &Result<Res>, latency: Duration) {
+        if let Err(err) = self.record(call, request, response, latency) {
+            warn!(call, error = %err, "failed to record cassette entry");
+        }
+    }
+}
+
+/// Wraps a live provider, quote source or executor and records each call to a cassette.
+pub struct Recording<T> { inner: T, cassette: Arc<CassetteWriter> }
+
+impl<T> Recording<T> {
+    pub fn new(inner: T, cassette: Arc<CassetteWriter>) -> Self { Self { inner, cassette } }
+
+    pub fn into_inner(self) -> T { self.inner }
+}
+
+#[async_trait]
+impl<T: MetadataProvider> MetadataProvider for Recording<T> {
+    async fn latest(&self) -> Result<SxMetadata> {
+        let started = Instant::now();
+        let response = self.inner.latest().await;
+        self.cassette.record_or_warn(CALL_METADATA, &(), &response, started.elapsed());
+        response
+    }
+}
+
+#[async_trait]
+impl<T: QuoteSource> QuoteSource for Recording<T> {
+    async fn best_quote(&self, request: &QuoteRequest) -> Result<Quote> {
+        let started = Instant::now();
+        let response = self.inner.best_quote(request).await;
+        self.cassette.record_or_warn(CALL_QUOTE, request, &response, started.elapsed());
+        response
+    }
+}
+
+#[async_trait]
+impl<T: OrderExecutor> OrderExecutor for Recording<T> {
+    async fn submit(&self, order: PreparedOrder) -> Result<OrderResponse> {
+        let started = Instant::now();
+        let response = self.inner.submit(order.clone()).await;
+        self.cassette.record_or_warn(CALL_SUBMIT, &order, &response, started.elapsed());
+        response
+    }
+
+    async fn lookup(&self, client_order_id: &ClientOrderId) -> Result<Option<OrderResponse>> {
+        let started = Instant::now();
+        let response = self.inner.lookup(client_order_id).await;
+        self.cassette.record_or_warn(CALL_LOOKUP, client_order_id, &response, started.elapsed());
+        response
+    }
+}
+
+/// Serves a cassette back in recorded order, per call kind, optionally with the original latency.
+pub struct Replay { calls: Mutex<HashMap<String, VecDeque<CassetteEntry>>>, with_latency: bool, strict: bool }
+
+impl Replay {
+    pub fn from_entries(entries: impl IntoIterator<Item = CassetteEntry>) -> Self {
+        let mut calls: HashMap<String, VecDeque<CassetteEntry>> = HashMap::new();
+        let mut entries: Vec<_> = entries.into_iter().collect();
+        entries.sort_by_key(|entry| entry.seq);
+        for entry in entries {
+            calls.entry(entry.call.clone()).or_default().push_back(entry);
+        }
+        Self { calls: Mutex::new(calls), with_latency: false, strict: true }
+    }
+
+    pub fn from_reader(reader: impl BufRead) -> Result<Self> {
+        let mut entries = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.map_err(|err| cassette_error(err.to_string()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = serde_json::from_str(&line).map_err(|err| cassette_error(format!("line {}: {err}", index + 1)))?;
+            entries.push(entry);
+        }
+        Ok(Self::from_entries(entries))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = File::open(path).map_err(|err| cassette_error(format!("{}: {err}", path.display())))?;
+        Self::from_reader(BufReader::new(file))
+    }
+
+    /// Sleeps for the recorded latency before answering each call.
+    pub fn with_latency(mut self, enabled: bool) -> Self {
+        self.with_latency = enabled;
+        self
+    }
+
+    /// When strict (the default), a request differing from the recorded one is an error.
+    pub fn strict(mut self, enabled: bool) -> Self {
+        self.strict = enabled;
+        self
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.lock().values().map(VecDeque::len).sum()
+    }
+
+    async fn serve<Req: Serialize, Res: DeserializeOwned>(&self, call: &str, request: &Req) -> Result<Res> {
+        let entry = self
+            .lock()
+            .get_mut(call)
+            .and_then(VecDeque::pop_front)
+            .ok_or_else(|| cassette_error(format!("no recorded `{call}` call left")))?;
+        if self.strict {
+            let actual = to_value(request)?;
+            if actual != entry.request {
+                return Err(cassette_error(format!("`{call}` #{} request mismatch: recorded {}, got {actual}", entry.seq, entry.request)));
+            }
+        }
+        if self.with_latency {
+            time::sleep(Duration::from_micros(entry.latency_us)).await;
+        }
+        let response: Result<Res> = serde_json::from_value(entry.response).map_err(|err| cassette_error(err.to_string()))?;
+        response
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, VecDeque<CassetteEntry>>> {
+        self.calls.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[async_trait]
+impl MetadataProvider for Replay {
+    async fn latest(&self) -> Result<SxMetadata> { self.serve(CALL_METADATA, &()).await }
+}
+
+#[async_trait]
+impl QuoteSource for Replay {
+    async fn best_quote(&self, request: &QuoteRequest) -> Result<Quote> { self.serve(CALL_QUOTE, request).await }
+}
+
+#[async_trait]
+impl OrderExecutor for Replay {
+    async fn submit(&self, order: PreparedOrder) -> Result<OrderResponse> { self.serve(CALL_SUBMIT, &order).await }
+
+    async fn lookup(&self, client_order_id: &ClientOrderId) -> Result<Option<OrderResponse>> { self.serve(CALL_LOOKUP, client_order_id).await }
+}
+
+/// Stores an `Instant` as its age in microseconds at serialization time so cassettes stay portable.
+pub(crate) mod instant_age {
+    use std::time::{Duration, Instant};
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(instant: &Instant, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let age = Instant::now().saturating_duration_since(*instant);
+        serializer.serialize_u64(u64::try_from(age.as_micros()).unwrap_or(u64::MAX))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Instant, D::Error> {
+        let age = Duration::from_micros(u64::deserialize(deserializer)?);
+        let now = Instant::now();
+        Ok(now.checked_sub(age).unwrap_or(now))
+    }
+}
+
+fn to_value<T: Serialize>(value: &T) -> Result<Value> {
+    serde_json::to_value(value).map_err(|err| cassette_error(err.to_string()))
+}
+
+fn cassette_error(message: String) -> SxClientError {
+    SxClientError::Cassette(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Fill, OrderStatus, SxClient};
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().expect("buffer").extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+    }
+
+    struct BrokenSink;
+
+    impl Write for BrokenSink {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> { Err(std::io::Error::other("disk full")) }
+
+        fn flush(&mut self) -> std::io::Result<()> { Ok(()) }
+    }
+
+    struct LiveVenue;
+
+    #[async_trait]
+    impl MetadataProvider for LiveVenue {
+        async fn latest(&self) -> Result<SxMetadata> {
+            Ok(SxMetadata {
+                odds_ladder_step: dec("0.01"),
+                odds_ladder_min: dec("1.01"),
+                odds_ladder_max: dec("20"),
+                betting_delay: Duration::from_millis(50),
+                heartbeat: Duration::from_millis(200),
+                min_odds_slippage: Decimal::ZERO,
+                max_odds_slippage: dec("0.03"),
+                fetched_at: Instant::now(),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl QuoteSource for LiveVenue {
+        async fn best_quote(&self, request: &QuoteRequest) -> Result<Quote> {
+            time::sleep(Duration::from_millis(5)).await;
+            Ok(Quote { market_uid: request.market_uid.clone(), side: request.side.clone(), odds: dec("2.034"), available_stake: dec("75") })
+        }
+    }
+
+    #[async_trait]
+    impl OrderExecutor for LiveVenue {
+        async fn submit(&self, order: PreparedOrder) -> Result<OrderResponse> {
+            if order.stake > dec("50") {
+                return Err(SxClientError::Executor("insufficient liquidity".into()));
+            }
+            Ok(OrderResponse { status: OrderStatus::Accepted, fills: vec![Fill { fill_id: "f1".into(), filled_stake: order.stake, odds: order.odds, accepted_at: Instant::now() }] })
+        }
+    }
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).expect("valid decimal")
+    }
+
+    fn bet(id: &str, stake: &str) -> crate::BetRequest {
+        crate::BetRequest { client_order_id: ClientOrderId::new(id), market_uid: "m1".into(), side: "back".into(), odds: dec("2.03"), stake: dec(stake), odds_slippage: dec("0.01") }
+    }
+
+    #[tokio::test]
+    async fn cassette_write_errors_do_not_mask_live_responses() {
+        let recording = Recording::new(LiveVenue, Arc::new(CassetteWriter::new(BrokenSink)));
+        let order = PreparedOrder { client_order_id: ClientOrderId::new("o1"), market_uid: "m1".into(), side: "back".into(), odds: dec("2.03"), stake: dec("10"), odds_slippage: dec("0.01"), heartbeat: Duration::from_millis(200), betting_delay: Duration::from_millis(50) };
+        let response = recording.submit(order).await.expect("live bet is reported");
+        assert_eq!(response.fills[0].filled_stake, dec("10"));
+    }
+
+    async fn record_session() -> Vec<u8> {
+        let buffer = SharedBuffer::default();
+        let cassette = Arc::new(CassetteWriter::new(buffer.clone()));
+        let client = SxClient::new(
+            Duration::from_secs(60),
+            Arc::new(Recording::new(LiveVenue, cassette.clone())),
+            Arc::new(Recording::new(LiveVenue, cassette.clone())),
+            Arc::new(Recording::new(LiveVenue, cassette)),
+        );
+        client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "back".into(), stake: dec("10") }).await.expect("quote");
+        client.place_bet(bet("o1", "10")).await.expect("bet");
+        client.place_bet(bet("o2", "60")).await.expect_err("liquidity error");
+        let bytes = buffer.0.lock().expect("buffer").clone();
+        bytes
+    }
+
+    #[tokio::test]
+    async fn recorded_session_replays_deterministically() {
+        let bytes = record_session().await;
+        let lines: Vec<CassetteEntry> = bytes.split(|b| *b == b'\n').filter(|l| !l.is_empty()).map(|l| serde_json::from_slice(l).expect("entry")).collect();
+        assert_eq!(lines.iter().map(|e| e.call.as_str()).collect::<Vec<_>>(), vec![CALL_METADATA, CALL_QUOTE, CALL_SUBMIT, CALL_SUBMIT]);
+        assert!(lines[1].latency_us >= 5_000);
+
+        let replay = Arc::new(Replay::from_reader(bytes.as_slice()).expect("cassette"));
+        let client = SxClient::new(Duration::from_secs(60), replay.clone(), replay.clone(), replay.clone());
+        let quote = client.get_best_quote(QuoteRequest { market_uid: "m1".into(), side: "back".into(), stake: dec("10") }).await.expect("replayed quote");
+        assert_eq!(quote.odds, dec("2.03"));
+        let execution = client.place_bet(bet("o1", "10")).await.expect("replayed bet");
+        assert_eq!(execution.fills[0].filled_stake, dec("10"));
+        let err = client.place_bet(bet("o2", "60")).await.expect_err("replayed error");
+        assert_eq!(err.code(), "E-SX-EXECUTOR");
+        assert_eq!(replay.remaining(), 0);
+    }
+
+    #[tokio::test]
+    async fn strict_replay_rejects_diverging_requests() {
+        let bytes = record_session().await;
+        let replay = Replay::from_reader(bytes.as_slice()).expect("cassette");
+        let err = replay.best_quote(&QuoteRequest { market_uid: "other".into(), side: "back".into(), stake: dec("10") }).await.expect_err("mismatch");
+        assert_eq!(err.code(), "E-SX-CASSETTE");
+        let err = replay.lookup(&ClientOrderId::new("o1")).await.expect_err("nothing recorded");
+        assert_eq!(err.code(), "E-SX-CASSETTE");
+    }
+
+    #[tokio::test]
+    async fn replay_can_reproduce_recorded_latency() {
+        let bytes = record_session().await;
+        let replay = Replay::from_reader(bytes.as_slice()).expect("cassette").with_latency(true).strict(false);
+        replay.latest().await.expect("metadata");
+        let started = Instant::now();
+        replay.best_quote(&QuoteRequest { market_uid: "m1".into(), side: "back".into(), stake: dec("10") }).await.expect("quote");
+        assert!(started.elapsed() >= Duration::from_millis(5));
+    }
+}
@@ -0,0 +1,290 @@
+use std::{sync::Mutex, time::{Duration, Instant}};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Result, SxClientError};
+
+/// Operation classes sharing SX rate limits; orders are served first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum OperationClass { Metadata, Quotes, Orders }
+
+impl OperationClass {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            OperationClass::Metadata => "metadata",
+            OperationClass::Quotes => "quotes",
+            OperationClass::Orders => "orders",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct BucketConfig { pub capacity: u32, pub refill_per_sec: f64 }
+
+/// Per-class buckets plus a shared budget; `order_reserve` shared tokens are kept for orders only.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct RateLimitConfig { pub metadata: BucketConfig, pub quotes: BucketConfig, pub orders: BucketConfig, pub shared: BucketConfig, #[serde(default)] pub order_reserve: u32 }
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            metadata: BucketConfig { capacity: 2, refill_per_sec: 1.0 },
+            quotes: BucketConfig { capacity: 20, refill_per_sec: 10.0 },
+            orders: BucketConfig { capacity: 10, refill_per_sec: 5.0 },
+            shared: BucketConfig { capacity: 25, refill_per_sec: 12.0 },
+            order_reserve: 5,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket { config: BucketConfig, tokens: f64, updated_at: Instant }
+
+impl TokenBucket {
+    fn new(config: BucketConfig, now: Instant) -> Self {
+        Self { config, tokens: f64::from(config.capacity), updated_at: now }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.config.refill_per_sec).min(f64::from(self.config.capacity));
+        self.updated_at = now;
+    }
+
+    fn available(&self, floor: f64) -> bool { self.tokens - floor >= 1.0 }
+
+    fn retry_after(&self, floor: f64) -> Duration {
+        if self.config.refill_per_sec <= 0.0 {
+            return Duration::MAX;
+        }
+        Duration::from_secs_f64(((1.0 + floor - self.tokens) / self.config.refill_per_sec).max(0.0))
+    }
+}
+
+#[derive(Debug)]
+struct Buckets { metadata: TokenBucket, quotes: TokenBucket, orders: TokenBucket, shared: TokenBucket }
+
+impl Buckets {
+    fn class(&mut self, class: OperationClass) -> &mut TokenBucket {
+        match class {
+            OperationClass::Metadata => &mut self.metadata,
+            OperationClass::Quotes => &mut self.quotes,
+            OperationClass::Orders => &mut self.orders,
+        }
+    }
+}
+
+/// Client-side token-bucket limiter; rejects instead of queueing so callers keep control of latency.
+#[derive(Debug)]
+pub struct RateLimiter { order_reserve: f64, buckets: Mutex<Buckets> }
+
+impl RateLimiter {
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self::new_at(config, Instant::now())
+    }
+
+    fn new_at(config: RateLimitConfig, now: Instant) -> Self {
+        let buckets = Buckets {
+            metadata: TokenBucket::new(config.metadata, now),
+            quotes: TokenBucket::new(config.quotes, now),
+            orders: TokenBucket::new(config.orders, now),
+            shared: TokenBucket::new(config.shared, now),
+        };
+        Self { order_reserve: f64::from(config.order_reserve), buckets: Mutex::new(buckets) }
+    }
+
+    pub fn try_acquire(&self, class: OperationClass) -> Result<()> {
+        self.try_acquire_at(class, Instant::now())
+    }
+
+    fn try_acquire_at(&self, class: OperationClass, now: Instant) -> Result<()> {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        buckets.shared.refill(now);
+        buckets.class(class).refill(now);
+        let shared_floor = if class == OperationClass::Orders { 0.0 } else { self.order_reserve };
+        if !buckets.class(class).available(0.0) {
+            let retry_after = buckets.class(class).retry_after(0.0);
+            return Err(SxClientError::RateLimited { class, retry_after });
+        }
+        if !buckets.shared.available(shared_floor) {
+            let retry_after = buckets.shared.retry_after(shared_floor);
+            return Err(SxClientError::RateLimited { class, retry_after });
+        }
+        buckets.class(class).tokens -= 1.0;
+        buckets.shared.tokens -= 1.0;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct CircuitBreakerConfig { pub failure_threshold: u32, pub cooldown_ms: u64 }
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self { Self { failure_threshold: 3, cooldown_ms: 30_000 } }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState { Closed, Open { until: Instant }, HalfOpen }
+
+impl CircuitState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CircuitState::Closed => "closed",
+            CircuitState::Open { .. } => "open",
+            CircuitState::HalfOpen => "half_open",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct BreakerInner { state: CircuitState, consecutive_failures: u32, probe_in_flight: bool }
+
+/// Opens after `failure_threshold` consecutive executor errors, then lets one probe through after the cooldown.
+#[derive(Debug)]
+pub struct CircuitBreaker { config: CircuitBreakerConfig, inner: Mutex<BreakerInner> }
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self { config, inner: Mutex::new(BreakerInner { state: CircuitState::Closed, consecutive_failures: 0, probe_in_flight: false }) }
+    }
+
+    pub fn state(&self) -> CircuitState {
+        self.state_at(Instant::now())
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.lock().consecutive_failures
+    }
+
+    pub fn before_call(&self) -> Result<()> {
+        self.before_call_at(Instant::now())
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.lock();
+        inner.state = CircuitState::Closed;
+        inner.consecutive_failures = 0;
+        inner.probe_in_flight = false;
+    }
+
+    /// Ends a call that said nothing about venue health, freeing a half-open probe without closing or reopening.
+    pub fn release_probe(&self) {
+        self.lock().probe_in_flight = false;
+    }
+
+    pub fn record_failure(&self) {
+        self.record_failure_at(Instant::now());
+    }
+
+    fn state_at(&self, now: Instant) -> CircuitState {
+        let mut inner = self.lock();
+        Self::advance(&mut inner, now);
+        inner.state
+    }
+
+    fn before_call_at(&self, now: Instant) -> Result<()> {
+        let mut inner = self.lock();
+        Self::advance(&mut inner, now);
+        match inner.state {
+            CircuitState::Closed => Ok(()),
+            CircuitState::Open { until } => Err(SxClientError::CircuitOpen { retry_after: until.saturating_duration_since(now) }),
+            CircuitState::HalfOpen if inner.probe_in_flight => Err(SxClientError::CircuitOpen { retry_after: Duration::ZERO }),
+            CircuitState::HalfOpen => {
+                inner.probe_in_flight = true;
+                Ok(())
+            }
+        }
+    }
+
+    fn record_failure_at(&self, now: Instant) {
+        let mut inner = self.lock();
+        inner.consecutive_failures = inner.consecutive_failures.saturating_add(1);
+        let reopen = matches!(inner.state, CircuitState::HalfOpen) || inner.consecutive_failures >= self.config.failure_threshold;
+        if reopen {
+            let until = now.checked_add(Duration::from_millis(self.config.cooldown_ms)).unwrap_or(now);
+            inner.state = CircuitState::Open { until };
+        }
+        inner.probe_in_flight = false;
+    }
+
+    fn advance(inner: &mut BreakerInner, now: Instant) {
+        if let CircuitState::Open { until } = inner.state {
+            if now >= until {
+                inner.state = CircuitState::HalfOpen;
+                inner.probe_in_flight = false;
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerInner> {
+        self.inner.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bucket(capacity: u32, refill_per_sec: f64) -> BucketConfig { BucketConfig { capacity, refill_per_sec } }
+
+    #[test]
+    fn bucket_rejects_when_empty_and_refills() {
+        let now = Instant::now();
+        let config = RateLimitConfig { quotes: bucket(2, 1.0), shared: bucket(100, 100.0), order_reserve: 0, ..RateLimitConfig::default() };
+        let limiter = RateLimiter::new_at(config, now);
+        assert!(limiter.try_acquire_at(OperationClass::Quotes, now).is_ok());
+        assert!(limiter.try_acquire_at(OperationClass::Quotes, now).is_ok());
+        let err = limiter.try_acquire_at(OperationClass::Quotes, now).expect_err("bucket empty");
+        assert_eq!(err.code(), "E-SX-RATE-LIMITED");
+        assert!(matches!(err, SxClientError::RateLimited { class: OperationClass::Quotes, retry_after } if retry_after <= Duration::from_secs(1)));
+        assert!(limiter.try_acquire_at(OperationClass::Quotes, now + Duration::from_secs(1)).is_ok());
+    }
+
+    #[test]
+    fn orders_keep_reserved_shared_tokens() {
+        let now = Instant::now();
+        let config = RateLimitConfig { quotes: bucket(10, 0.0), orders: bucket(10, 0.0), shared: bucket(3, 0.0), order_reserve: 2, ..RateLimitConfig::default() };
+        let limiter = RateLimiter::new_at(config, now);
+        assert!(limiter.try_acquire_at(OperationClass::Quotes, now).is_ok());
+        let err = limiter.try_acquire_at(OperationClass::Quotes, now).expect_err("quotes cannot eat the order reserve");
+        assert!(matches!(err, SxClientError::RateLimited { retry_after: Duration::MAX, .. }));
+        assert!(limiter.try_acquire_at(OperationClass::Orders, now).is_ok());
+        assert!(limiter.try_acquire_at(OperationClass::Orders, now).is_ok());
+        assert!(limiter.try_acquire_at(OperationClass::Orders, now).is_err());
+    }
+
+    #[test]
+    fn breaker_opens_after_consecutive_failures_and_probes_after_cooldown() {
+        let now = Instant::now();
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig { failure_threshold: 2, cooldown_ms: 1_000 });
+        breaker.record_failure_at(now);
+        assert_eq!(breaker.state_at(now), CircuitState::Closed);
+        breaker.record_failure_at(now);
+        assert!(matches!(breaker.state_at(now), CircuitState::Open { .. }));
+        let err = breaker.before_call_at(now).expect_err("open circuit");
+        assert_eq!(err.code(), "E-SX-CIRCUIT-OPEN");
+
+        let later = now + Duration::from_secs(1);
+        assert_eq!(breaker.state_at(later), CircuitState::HalfOpen);
+        assert!(breaker.before_call_at(later).is_ok(), "single probe allowed");
+        assert!(breaker.before_call_at(later).is_err(), "second probe rejected");
+        breaker.record_failure_at(later);
+        assert!(matches!(breaker.state_at(later), CircuitState::Open { .. }));
+
+        let probe = later + Duration::from_secs(1);
+        assert!(breaker.before_call_at(probe).is_ok());
+        breaker.record_success();
+        assert_eq!(breaker.state_at(probe), CircuitState::Closed);
+        assert_eq!(breaker.consecutive_failures(), 0);
+    }
+
+    #[test]
+    fn success_resets_failure_streak() {
+        let now = Instant::now();
+        let breaker = CircuitBreaker::new(CircuitBreakerConfig { failure_threshold: 2, cooldown_ms: 1_000 });
+        breaker.record_failure_at(now);
+        breaker.record_success();
+        breaker.record_failure_at(now);
+        assert_eq!(breaker.state_at(now), CircuitState::Closed);
+    }
+}
@@ -0,0 +1,73 @@
+use std::{env, fs, process};
+
+use normalization::{SeedCatalogue, SeedReport, UidVersion};
+
+const USAGE: &str = "usage: seed_check [--seed data/market_uid_seed.csv] [--version v1] [--rewrite] [--json]";
+
+struct Args {
+    seed: String,
+    version: UidVersion,
+    rewrite: bool,
+    json: bool,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args { seed: "data/market_uid_seed.csv".to_string(), version: UidVersion::V1, rewrite: false, json: false };
+    let mut iter = env::args().skip(1);
+    while let Some(flag) = iter.next() {
+        let mut value = || iter.next().ok_or_else(|| format!("missing value for {flag}"));
+        match flag.as_str() {
+            "--seed" => args.seed = value()?,
+            "--version" => args.version = value()?.parse().map_err(|err| format!("{err}"))?,
+            "--rewrite" => args.rewrite = true,
+            "--json" => args.json = true,
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            other => return Err(format!("unknown argument `{other}`\n{USAGE}")),
+        }
+    }
+    Ok(args)
+}
+
+/// Validates the seed, regenerating UIDs in place first when `--rewrite` is set.
+fn run(args: &Args) -> Result<(SeedReport, usize), Box<dyn std::error::Error>> {
+    let mut catalogue = SeedCatalogue::read(fs::File::open(&args.seed)?)?;
+    let mut rewritten = 0;
+    if args.rewrite {
+        rewritten = catalogue.regenerate(args.version);
+        if rewritten > 0 {
+            catalogue.save(&args.seed)?;
+        }
+    }
+    Ok((catalogue.validate(args.version), rewritten))
+}
+
+fn main() {
+    let args = parse_args().unwrap_or_else(|message| {
+        eprintln!("{message}");
+        process::exit(2);
+    });
+    let (report, rewritten) = match run(&args) {
+        Ok(outcome) => outcome,
+        Err(error) => {
+            eprintln!("[ERR] seed check failed: {error}");
+            process::exit(1);
+        }
+    };
+    if args.json {
+        let body = serde_json::json!({ "seed": args.seed, "rewritten": rewritten, "report": report });
+        println!("{body}");
+    } else {
+        if rewritten > 0 {
+            eprintln!("[OK] rewrote {rewritten} UIDs in {}", args.seed);
+        }
+        for issue in &report.issues {
+            eprintln!("[ERR] {issue}");
+        }
+        if report.is_clean() {
+            eprintln!("[OK] {} rows of {} match {}", report.rows, args.seed, report.version);
+        }
+    }
+    if !report.is_clean() {
+        process::exit(1);
+    }
+}
@@ -0,0 +1,53 @@
+use std::{env, fs, io, process};
+
+use normalization::{build_mapping, read_seed, write_mapping, UidVersion};
+
+const USAGE: &str = "usage: uid_migrate [--seed data/market_uid_seed.csv] [--out <mapping.csv>] [--from v1] [--to v2]";
+
+struct Args {
+    seed: String,
+    out: Option<String>,
+    from: UidVersion,
+    to: UidVersion,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args { seed: "data/market_uid_seed.csv".to_string(), out: None, from: UidVersion::V1, to: UidVersion::V2 };
+    let mut iter = env::args().skip(1);
+    while let Some(flag) = iter.next() {
+        let mut value = || iter.next().ok_or_else(|| format!("missing value for {flag}"));
+        match flag.as_str() {
+            "--seed" => args.seed = value()?,
+            "--out" => args.out = Some(value()?),
+            "--from" => args.from = value()?.parse().map_err(|err| format!("{err}"))?,
+            "--to" => args.to = value()?.parse().map_err(|err| format!("{err}"))?,
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            other => return Err(format!("unknown argument `{other}`\n{USAGE}")),
+        }
+    }
+    Ok(args)
+}
+
+fn run(args: &Args) -> Result<usize, Box<dyn std::error::Error>> {
+    let seed = read_seed(fs::File::open(&args.seed)?)?;
+    let mappings = build_mapping(&seed, args.from, args.to)?;
+    match &args.out {
+        Some(path) => write_mapping(&mappings, fs::File::create(path)?)?,
+        None => write_mapping(&mappings, io::stdout().lock())?,
+    }
+    Ok(mappings.len())
+}
+
+fn main() {
+    let args = parse_args().unwrap_or_else(|message| {
+        eprintln!("{message}");
+        process::exit(2);
+    });
+    match run(&args) {
+        Ok(count) => eprintln!("[OK] {count} UIDs mapped {} -> {} from {}", args.from, args.to, args.seed),
+        Err(error) => {
+            eprintln!("[ERR] uid migration failed: {error}");
+            process::exit(1);
+        }
+    }
+}